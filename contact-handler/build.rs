@@ -0,0 +1,220 @@
+// Build-time asset processing for the server binaries
+//   SCC_BUILD_TIME     - Last-Modified date of every embedded asset (when
+//                        they, or this script, last changed)
+//   built_assets.rs    - for each embedded text asset: the body with asset
+//                        references rewritten to fingerprinted URLs, and its
+//                        brotli and gzip variants, so serving them compressed
+//                        costs nothing at runtime; plus the table mapping
+//                        fingerprinted URLs (css/style.min.<hash>.css) back to
+//                        the embedded files
+//
+// The assets are found the same way the Assets #[include] globs in assets.rs
+// match them ("*.html" spans directories). Each entry records the SHA-256 of
+// its source, and is only used for those exact bytes.
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directories the Assets #[exclude] list skips, plus tooling output
+const SKIP_DIRS: &[&str] = &["contact-handler", "android-app", "downloaded", "scripts", "node_modules", ".git"];
+const TEXT_SUFFIXES: &[&str] = &[".html", ".min.css", ".min.js"];
+/// Hex digits of the content hash put into fingerprinted file names
+const FINGERPRINT_LEN: usize = 10;
+
+/// Assets served as immutable, which therefore need a new URL when they change
+fn fingerprinted(key: &str) -> bool {
+    (key.starts_with("images/") && [".webp", ".png", ".ico"].iter().any(|ext| key.ends_with(ext)))
+        || (key.starts_with("css/") && key.ends_with(".min.css"))
+        || (key.starts_with("js/") && key.ends_with(".min.js"))
+}
+
+fn is_text(key: &str) -> bool {
+    TEXT_SUFFIXES.iter().any(|suffix| key.ends_with(suffix))
+}
+
+fn collect_assets(root: &Path, dir: &Path, found: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !(dir == root && SKIP_DIRS.contains(&name.as_str())) {
+                // Picks up new files; the root itself would also scan target/
+                println!("cargo:rerun-if-changed={}", path.display());
+                collect_assets(root, &path, found);
+            }
+        } else {
+            let key = path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/");
+            if is_text(&key) || fingerprinted(&key) {
+                // A directory entry only notices files coming and going
+                println!("cargo:rerun-if-changed={}", path.display());
+                found.push((key, path));
+            }
+        }
+    }
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// "css/style.min.css" -> "css/style.min.0123456789.css"
+fn fingerprinted_path(key: &str, hash: &[u8; 32]) -> String {
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    let (stem, ext) = key.rsplit_once('.').unwrap_or((key, ""));
+    format!("{}.{}.{}", stem, &hex[..FINGERPRINT_LEN], ext)
+}
+
+/// Embedded path a reference in `document` points at, if it's a local one
+fn resolve_reference(document: &str, reference: &str) -> Option<String> {
+    if reference.contains("://") || reference.starts_with("//") || reference.starts_with("data:") {
+        return None;
+    }
+    let mut segments: Vec<&str> = if reference.starts_with('/') {
+        Vec::new()
+    } else {
+        document.split('/').collect::<Vec<_>>().split_last().map(|(_, dir)| dir.to_vec()).unwrap_or_default()
+    };
+    for segment in reference.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Rewrite every reference to a fingerprinted asset (attribute values, CSS
+/// url()s) to its fingerprinted URL, keeping the reference's own form
+fn rewrite_references(document: &str, text: &str, fingerprints: &BTreeMap<String, String>) -> String {
+    let is_delimiter = |c: char| c.is_whitespace() || "\"'()<>,`".contains(c);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let token_len = rest.find(is_delimiter).unwrap_or(rest.len());
+        if token_len == 0 {
+            let c = rest.chars().next().unwrap();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let token = &rest[..token_len];
+        rest = &rest[token_len..];
+
+        let path_len = token.find(['?', '#']).unwrap_or(token.len());
+        let (path, suffix) = token.split_at(path_len);
+        let hashed = resolve_reference(document, path).and_then(|key| fingerprints.get(&key));
+        match hashed {
+            Some(hashed) => {
+                let name = hashed.rsplit('/').next().unwrap();
+                let dir_len = path.rfind('/').map(|i| i + 1).unwrap_or(0);
+                out.push_str(&path[..dir_len]);
+                out.push_str(name);
+                out.push_str(suffix);
+            }
+            None => out.push_str(token),
+        }
+    }
+    out
+}
+
+fn brotli(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+        writer.write_all(data).expect("brotli compression failed");
+    }
+    out
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(data).expect("gzip compression failed");
+    encoder.finish().expect("gzip compression failed")
+}
+
+/// Write `data` to `out_dir` and return an include_bytes! expression for it
+fn include(out_dir: &Path, name: &str, data: &[u8]) -> String {
+    let path = out_dir.join(name);
+    fs::write(&path, data).expect("failed to write built asset");
+    format!("Some(include_bytes!({:?}) as &[u8])", path.display().to_string())
+}
+
+/// Compressed variant, or None when compressing didn't make the body smaller
+fn variant(out_dir: &Path, name: &str, body_len: usize, data: Vec<u8>) -> String {
+    if data.len() >= body_len {
+        return "None".to_string();
+    }
+    include(out_dir, name, &data)
+}
+
+fn main() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    println!("cargo:rustc-env=SCC_BUILD_TIME={}", now);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let out = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let out_dir = out.join("built_assets");
+    fs::create_dir_all(&out_dir).expect("failed to create built_assets dir");
+
+    let mut assets = Vec::new();
+    collect_assets(&root, &root, &mut assets);
+    assets.sort();
+    let sources: BTreeMap<String, Vec<u8>> =
+        assets.iter().map(|(key, path)| (key.clone(), fs::read(path).expect("failed to read asset"))).collect();
+
+    // Images first, then the CSS/JS that may reference them, then the HTML
+    // that references both
+    let mut fingerprints: BTreeMap<String, String> = BTreeMap::new();
+    let mut bodies: BTreeMap<String, String> = BTreeMap::new();
+    for (key, data) in sources.iter().filter(|(key, _)| fingerprinted(key) && !is_text(key)) {
+        fingerprints.insert(key.clone(), fingerprinted_path(key, &sha256(data)));
+    }
+    let mut scripts_and_styles = BTreeMap::new();
+    for (key, data) in sources.iter().filter(|(key, _)| fingerprinted(key) && is_text(key)) {
+        let body = rewrite_references(key, &String::from_utf8_lossy(data), &fingerprints);
+        scripts_and_styles.insert(key.clone(), fingerprinted_path(key, &sha256(body.as_bytes())));
+        bodies.insert(key.clone(), body);
+    }
+    fingerprints.extend(scripts_and_styles);
+    for (key, data) in sources.iter().filter(|(key, _)| !fingerprinted(key)) {
+        bodies.insert(key.clone(), rewrite_references(key, &String::from_utf8_lossy(data), &fingerprints));
+    }
+
+    let mut table = String::from("static BUILT_ASSETS: &[BuiltAsset] = &[\n");
+    for (index, (key, body)) in bodies.iter().enumerate() {
+        let source = &sources[key];
+        let body = body.as_bytes();
+        let rewritten = if body == source.as_slice() { "None".to_string() } else { include(&out_dir, &index.to_string(), body) };
+        let br = variant(&out_dir, &format!("{}.br", index), body.len(), brotli(body));
+        let gz = variant(&out_dir, &format!("{}.gz", index), body.len(), gzip(body));
+        let _ = writeln!(
+            table,
+            "    BuiltAsset {{ path: {:?}, source_sha256: {:?}, body: {}, sha256: {:?}, br: {}, gzip: {} }},",
+            key,
+            sha256(source),
+            rewritten,
+            sha256(body),
+            br,
+            gz
+        );
+    }
+    table.push_str("];\n\nstatic FINGERPRINTS: &[(&str, &str)] = &[\n");
+    let mut by_hashed: Vec<(&String, &String)> = fingerprints.iter().map(|(key, hashed)| (hashed, key)).collect();
+    by_hashed.sort();
+    for (hashed, key) in by_hashed {
+        let _ = writeln!(table, "    ({:?}, {:?}),", hashed, key);
+    }
+    table.push_str("];\n");
+
+    fs::write(out.join("built_assets.rs"), table).expect("failed to write built_assets.rs");
+}
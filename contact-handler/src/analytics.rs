@@ -0,0 +1,471 @@
+// First-party page-view analytics
+// Every public HTML page the server answers is counted per day, path,
+// referring site and language, without cookies, scripts or third parties.
+// Admin, record and API routes, the honeypot pages and the decoys (everything
+// robots.txt disallows) aren't counted, nor are crawlers or prefetches.
+//
+// Unique visitors are counted from a SHA-256 of the client address and
+// User-Agent under a random salt that lives only in memory and is replaced
+// every day (and on restart), so the same visitor can't be recognised across
+// days and nothing stored leads back to an address. Only the counts are kept:
+// they're added up in memory and appended to page_views.csv as increments
+// every analytics.flush_secs, when the dashboard is opened and on shutdown.
+//
+// /contact-admin/analytics charts the last 30 days (?days= for another range)
+// with the top pages, referrers and languages.
+//
+// Configuration ([analytics] in config.rs):
+//   enabled    - Count page views (ANALYTICS)
+//   flush_secs - Seconds between writes to page_views.csv (ANALYTICS_FLUSH_SECS)
+
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Local, NaiveDate};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::billing::{admin_page, unauthorized, ADMIN_STYLE};
+use crate::lang::Lang;
+use crate::{check_auth, config, html_escape, lang, public_url, records, seo};
+
+/// Path of the row holding the whole site's views and visitors for a day
+pub const SITE: &str = "*";
+
+pub const DIRECT: &str = "(direct)";
+pub const INTERNAL: &str = "(internal)";
+
+/// Rows shown in each of the dashboard's top lists
+const TOP: usize = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ViewKey {
+    /// YYYY-MM-DD, local time
+    pub day: String,
+    pub path: String,
+    pub referrer: String,
+    pub lang: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub views: u64,
+    pub visitors: u64,
+}
+
+impl Counts {
+    fn add(&mut self, other: Counts) {
+        self.views += other.views;
+        self.visitors += other.visitors;
+    }
+}
+
+/// Counts not yet written out, and the visitors already seen today
+struct Tally {
+    day: NaiveDate,
+    salt: [u8; 32],
+    seen: HashSet<[u8; 16]>,
+    pending: HashMap<ViewKey, Counts>,
+    last_flush: Instant,
+}
+
+fn new_salt() -> [u8; 32] {
+    let mut salt = [0; 32];
+    salt[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    salt[16..].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    salt
+}
+
+impl Tally {
+    fn new(day: NaiveDate) -> Tally {
+        Tally { day, salt: new_salt(), seen: HashSet::new(), pending: HashMap::new(), last_flush: Instant::now() }
+    }
+
+    /// Whether `visitor` is new today within `scope`
+    fn first_visit(&mut self, visitor: &str, scope: &str) -> bool {
+        let hash = Sha256::new().chain_update(self.salt).chain_update(visitor).chain_update([0]).chain_update(scope).finalize();
+        let mut short = [0; 16];
+        short.copy_from_slice(&hash[..16]);
+        self.seen.insert(short)
+    }
+
+    /// Count one view of `path` by `visitor` (address and User-Agent)
+    fn count(&mut self, day: NaiveDate, path: &str, referrer: &str, lang: &str, visitor: &str) {
+        if day != self.day {
+            self.day = day;
+            self.salt = new_salt();
+            self.seen.clear();
+        }
+        let day = day.format("%Y-%m-%d").to_string();
+        let page = ViewKey { day: day.clone(), path: path.to_string(), referrer: referrer.to_string(), lang: lang.to_string() };
+        let site = ViewKey { day, path: SITE.to_string(), referrer: String::new(), lang: String::new() };
+        for key in [page, site] {
+            let scope = format!("{}\n{}\n{}", key.path, key.referrer, key.lang);
+            let visitors = u64::from(self.first_visit(visitor, &scope));
+            self.pending.entry(key).or_default().add(Counts { views: 1, visitors });
+        }
+    }
+}
+
+fn tally() -> &'static Mutex<Tally> {
+    static TALLY: OnceLock<Mutex<Tally>> = OnceLock::new();
+    TALLY.get_or_init(|| Mutex::new(Tally::new(Local::now().date_naive())))
+}
+
+/// Crawlers, link checkers and requests with no User-Agent at all
+fn is_bot(user_agent: &str) -> bool {
+    let ua = user_agent.to_ascii_lowercase();
+    ua.trim().is_empty() || ["bot", "crawl", "spider", "slurp", "headless", "curl/", "wget/", "python-", "preview"].iter().any(|word| ua.contains(word))
+}
+
+/// Host of a URL, lowercased, without port, credentials or a leading "www."
+fn host_of(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, rest)| rest)?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = if host.starts_with('[') { host.split_inclusive(']').next()? } else { host.split(':').next()? };
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    (!host.is_empty()).then_some(host)
+}
+
+/// Referring site for the Referer header: "(direct)" when there isn't one,
+/// "(internal)" for links between the site's own pages
+fn referrer(referer: Option<&str>, own_hosts: &[&str]) -> String {
+    match referer.and_then(host_of) {
+        None => DIRECT.to_string(),
+        Some(host) if own_hosts.iter().any(|own| host_of(&format!("http://{}", own)).as_deref() == Some(host.as_str())) => INTERNAL.to_string(),
+        Some(host) => host,
+    }
+}
+
+fn header_str<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    req.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Count a view of `asset_path`, the page a request was answered with
+pub fn record_view(req: &HttpRequest, asset_path: &str) {
+    let settings = &config::get().analytics;
+    if !settings.enabled || req.method() != actix_web::http::Method::GET || !asset_path.ends_with(".html") || !seo::is_public_page(asset_path) {
+        return;
+    }
+    let user_agent = header_str(req, "User-Agent").unwrap_or("");
+    let prefetch = ["Purpose", "Sec-Purpose", "X-Moz"].iter().any(|name| header_str(req, name).is_some_and(|v| v.contains("prefetch")));
+    if prefetch || is_bot(user_agent) {
+        return;
+    }
+    let info = req.connection_info();
+    let public_host = public_url().split_once("://").map(|(_, host)| host).unwrap_or("");
+    let from = referrer(header_str(req, header::REFERER.as_str()), &[info.host(), public_host]);
+    let visitor = format!("{}\n{}", info.realip_remote_addr().unwrap_or(""), user_agent);
+
+    let due = {
+        let mut tally = tally().lock().unwrap_or_else(|e| e.into_inner());
+        tally.count(Local::now().date_naive(), &seo::page_url(asset_path), &from, lang::page_lang(asset_path).code(), &visitor);
+        tally.last_flush.elapsed().as_secs() >= settings.flush_secs
+    };
+    if due {
+        flush();
+    }
+}
+
+/// Append the counts gathered since the last flush to page_views.csv
+pub fn flush() {
+    let pending = {
+        let mut tally = tally().lock().unwrap_or_else(|e| e.into_inner());
+        tally.last_flush = Instant::now();
+        std::mem::take(&mut tally.pending)
+    };
+    let mut failed = HashMap::new();
+    for (key, counts) in pending {
+        if let Err(e) = records::append_page_views(&key, counts) {
+            log::error!("Analytics: could not save page views for {} {}: {}", key.day, key.path, e);
+            failed.insert(key, counts);
+        }
+    }
+    if !failed.is_empty() {
+        let mut tally = tally().lock().unwrap_or_else(|e| e.into_inner());
+        for (key, counts) in failed {
+            tally.pending.entry(key).or_default().add(counts);
+        }
+    }
+}
+
+/// Dashboard figures for the days `first..=last`
+#[derive(Debug, Default)]
+struct Summary {
+    /// Site-wide counts for every day in the range, oldest first
+    days: Vec<(NaiveDate, Counts)>,
+    total: Counts,
+    pages: Vec<(String, u64)>,
+    referrers: Vec<(String, u64)>,
+    langs: Vec<(String, u64)>,
+}
+
+/// Entries by descending count, then name
+fn ranked(counts: HashMap<String, u64>, limit: usize) -> Vec<(String, u64)> {
+    let mut ranked: Vec<(String, u64)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    ranked
+}
+
+fn summarize(rows: &[(ViewKey, Counts)], first: NaiveDate, last: NaiveDate) -> Summary {
+    let mut days: BTreeMap<NaiveDate, Counts> = first.iter_days().take_while(|day| *day <= last).map(|day| (day, Counts::default())).collect();
+    let (mut pages, mut referrers, mut langs) = (HashMap::new(), HashMap::new(), HashMap::new());
+    for (key, counts) in rows {
+        let Ok(day) = NaiveDate::parse_from_str(&key.day, "%Y-%m-%d") else { continue };
+        let Some(daily) = days.get_mut(&day) else { continue };
+        if key.path == SITE {
+            daily.add(*counts);
+        } else {
+            *pages.entry(key.path.clone()).or_default() += counts.views;
+            *referrers.entry(key.referrer.clone()).or_default() += counts.views;
+            *langs.entry(key.lang.clone()).or_default() += counts.views;
+        }
+    }
+    let mut total = Counts::default();
+    days.values().for_each(|counts| total.add(*counts));
+    Summary {
+        days: days.into_iter().collect(),
+        total,
+        pages: ranked(pages, TOP),
+        referrers: ranked(referrers, TOP),
+        langs: ranked(langs, TOP),
+    }
+}
+
+/// Daily views as bars with visitors as a line over them
+fn render_chart(days: &[(NaiveDate, Counts)], lang: Lang) -> String {
+    const WIDTH: f64 = 720.0;
+    const HEIGHT: f64 = 180.0;
+    let max = days.iter().map(|(_, counts)| counts.views).max().unwrap_or(0).max(1) as f64;
+    let step = WIDTH / days.len().max(1) as f64;
+    let y = |value: u64| HEIGHT - value as f64 / max * HEIGHT;
+    let mut bars = String::new();
+    let mut line = Vec::new();
+    let (t_views, t_visitors) = (lang.pick("views", "visitas"), lang.pick("visitors", "visitantes"));
+    for (i, (day, counts)) in days.iter().enumerate() {
+        let x = i as f64 * step;
+        bars.push_str(&format!(
+            r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#93c5fd"><title>{}: {} {}, {} {}</title></rect>"##,
+            x + step * 0.1,
+            y(counts.views),
+            step * 0.8,
+            HEIGHT - y(counts.views),
+            day.format(lang.pick("%a %b %-d", "%d/%m/%Y")),
+            counts.views,
+            t_views,
+            counts.visitors,
+            t_visitors
+        ));
+        line.push(format!("{:.1},{:.1}", x + step / 2.0, y(counts.visitors)));
+    }
+    let short = lang.pick("%b %-d", "%d/%m");
+    let first = days.first().map(|(day, _)| day.format(short).to_string()).unwrap_or_default();
+    let last = days.last().map(|(day, _)| day.format(short).to_string()).unwrap_or_default();
+    format!(
+        r##"<svg viewBox="0 -10 {w} {h}" width="100%" role="img" aria-label="{aria}">
+<line x1="0" y1="{base}" x2="{w}" y2="{base}" stroke="#cbd5e1"/>{bars}
+<polyline points="{line}" fill="none" stroke="#1d4ed8" stroke-width="2"/>
+<text x="0" y="{label}" font-size="12" fill="#64748b">{first}</text><text x="{w}" y="{label}" font-size="12" fill="#64748b" text-anchor="end">{last}</text>
+<text x="0" y="0" font-size="12" fill="#64748b">{max} {t_views}</text>
+</svg>"##,
+        w = WIDTH,
+        h = HEIGHT + 30.0,
+        base = HEIGHT,
+        label = HEIGHT + 16.0,
+        bars = bars,
+        line = line.join(" "),
+        first = first,
+        last = last,
+        max = max as u64,
+        t_views = t_views,
+        aria = lang.pick("Daily page views and visitors", "Visitas diarias y visitantes"),
+    )
+}
+
+fn render_table(title: &str, entries: &[(String, u64)], total: u64, lang: Lang) -> String {
+    let rows: String = entries
+        .iter()
+        .map(|(name, views)| {
+            format!(
+                r#"<tr><td>{name}</td><td class="num">{views}</td><td><progress max="{total}" value="{views}"></progress></td></tr>"#,
+                name = html_escape(name),
+                views = views,
+                total = total.max(1),
+            )
+        })
+        .collect();
+    format!(
+        r#"<h2>{title}</h2>
+<table><thead><tr><th>{title}</th><th>{t_views}</th><th></th></tr></thead>
+<tbody>{rows}</tbody></table>"#,
+        title = title,
+        t_views = lang.pick("Views", "Visitas"),
+        rows = if rows.is_empty() {
+            format!(r#"<tr><td colspan="3">{}</td></tr>"#, lang.pick("No page views yet.", "Aún no hay visitas."))
+        } else {
+            rows
+        },
+    )
+}
+
+#[derive(Deserialize)]
+struct RangeQuery {
+    days: Option<u32>,
+}
+
+async fn analytics_admin(req: HttpRequest, query: web::Query<RangeQuery>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    flush();
+    let days = query.days.unwrap_or(30).clamp(1, 366);
+    let last = Local::now().date_naive();
+    let first = last - Duration::days(i64::from(days) - 1);
+    let summary = summarize(&records::load_page_views(), first, last);
+    let lang = lang::negotiate(&req);
+    let t = |en, es| lang.pick(en, es);
+    let t_days = t("days", "días");
+    let ranges: String = [7, 30, 90, 365]
+        .iter()
+        .map(|n| if *n == days { format!("<strong>{} {}</strong>", n, t_days) } else { format!(r#"<a href="?days={n}">{n} {t_days}</a>"#, n = n, t_days = t_days) })
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let body = if config::get().analytics.enabled {
+        String::new()
+    } else {
+        format!(
+            r#"<p class="card">{} (<code>analytics.enabled</code> / <code>ANALYTICS</code>).</p>"#,
+            t("Page views aren't being counted", "No se están contando las visitas")
+        )
+    };
+
+    admin_page(lang, format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>{style}</style>
+</head>
+<body>
+    <div class="container">
+        <a class="back-link" href="/contact-admin">&larr; {t_back}</a>
+        <h1>{t_title}</h1>
+        {body}
+        <p>{ranges}</p>
+        <p class="card"><strong>{views}</strong> {t_views}, <strong>{visitors}</strong> {t_visitors}, {first} {t_to} {last}.</p>
+        {chart}
+        {pages}
+        {referrers}
+        {langs}
+    </div>
+</body>
+</html>"#,
+        lang = lang.code(),
+        style = ADMIN_STYLE,
+        body = body,
+        ranges = ranges,
+        views = summary.total.views,
+        visitors = summary.total.visitors,
+        first = first.format("%Y-%m-%d"),
+        last = last.format("%Y-%m-%d"),
+        chart = render_chart(&summary.days, lang),
+        pages = render_table(t("Pages", "Páginas"), &summary.pages, summary.total.views, lang),
+        referrers = render_table(t("Referrers", "Referencias"), &summary.referrers, summary.total.views, lang),
+        langs = render_table(t("Languages", "Idiomas"), &summary.langs, summary.total.views, lang),
+        t_title = t("Analytics", "Analíticas"),
+        t_back = t("Back to Admin", "Volver al panel"),
+        t_views = t("page views", "visitas"),
+        t_visitors = t("daily unique visitors", "visitantes únicos diarios"),
+        t_to = t("to", "a"),
+    ))
+}
+
+pub fn configure_analytics(cfg: &mut web::ServiceConfig) {
+    cfg.route("/contact-admin/analytics", web::get().to(analytics_admin));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_referrer() {
+        let own = ["southcitycomputer.com", "localhost:8080"];
+        assert_eq!(referrer(None, &own), DIRECT);
+        assert_eq!(referrer(Some("not a url"), &own), DIRECT);
+        assert_eq!(referrer(Some("https://www.Google.com/search?q=repair"), &own), "google.com");
+        assert_eq!(referrer(Some("https://user@news.example.org:8443/a#b"), &own), "news.example.org");
+        assert_eq!(referrer(Some("https://www.southcitycomputer.com/projects"), &own), INTERNAL);
+        assert_eq!(referrer(Some("http://localhost:8080/"), &own), INTERNAL);
+    }
+
+    #[test]
+    fn test_bots_skipped() {
+        assert!(is_bot(""));
+        assert!(is_bot("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"));
+        assert!(is_bot("curl/8.5.0"));
+        assert!(!is_bot("Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0"));
+    }
+
+    #[test]
+    fn test_unique_visitors_per_day() {
+        let monday = day("2026-03-02");
+        let mut tally = Tally::new(monday);
+        tally.count(monday, "/", DIRECT, "en", "10.0.0.1\nFirefox");
+        tally.count(monday, "/", DIRECT, "en", "10.0.0.1\nFirefox");
+        tally.count(monday, "/projects.html", INTERNAL, "en", "10.0.0.1\nFirefox");
+        tally.count(monday, "/", DIRECT, "en", "10.0.0.2\nFirefox");
+        let key = |day: &str, path: &str, referrer: &str| ViewKey {
+            day: day.to_string(),
+            path: path.to_string(),
+            referrer: referrer.to_string(),
+            lang: if path == SITE { String::new() } else { "en".to_string() },
+        };
+        assert_eq!(tally.pending[&key("2026-03-02", "/", DIRECT)], Counts { views: 3, visitors: 2 });
+        assert_eq!(tally.pending[&key("2026-03-02", "/projects.html", INTERNAL)], Counts { views: 1, visitors: 1 });
+        assert_eq!(tally.pending[&key("2026-03-02", SITE, "")], Counts { views: 4, visitors: 2 });
+
+        // A new day starts a new salt: the same visitor counts again
+        let salt = tally.salt;
+        tally.count(day("2026-03-03"), "/", DIRECT, "en", "10.0.0.1\nFirefox");
+        assert_ne!(tally.salt, salt);
+        assert_eq!(tally.pending[&key("2026-03-03", SITE, "")], Counts { views: 1, visitors: 1 });
+    }
+
+    #[test]
+    fn test_summarize() {
+        let row = |day: &str, path: &str, referrer: &str, lang: &str, views, visitors| {
+            (ViewKey { day: day.into(), path: path.into(), referrer: referrer.into(), lang: lang.into() }, Counts { views, visitors })
+        };
+        let rows = vec![
+            row("2026-03-01", "/", DIRECT, "en", 5, 5),
+            row("2026-03-01", SITE, "", "", 5, 5),
+            row("2026-03-02", "/", "google.com", "en", 2, 1),
+            row("2026-03-02", "/projects-es.html", DIRECT, "es", 3, 2),
+            row("2026-03-02", SITE, "", "", 4, 2),
+            // Increments of the same day add up
+            row("2026-03-02", "/", "google.com", "en", 1, 1),
+            row("2026-03-02", SITE, "", "", 2, 1),
+        ];
+        let summary = summarize(&rows, day("2026-03-02"), day("2026-03-04"));
+        assert_eq!(summary.days.len(), 3);
+        assert_eq!(summary.days[0].1, Counts { views: 6, visitors: 3 });
+        assert_eq!(summary.days[2].1, Counts::default());
+        assert_eq!(summary.total, Counts { views: 6, visitors: 3 });
+        assert_eq!(summary.pages, vec![("/".to_string(), 3), ("/projects-es.html".to_string(), 3)]);
+        assert_eq!(summary.referrers, vec![(DIRECT.to_string(), 3), ("google.com".to_string(), 3)]);
+        assert_eq!(summary.langs, vec![("en".to_string(), 3), ("es".to_string(), 3)]);
+        assert!(render_chart(&summary.days, Lang::En).contains("<polyline"));
+    }
+}
@@ -0,0 +1,483 @@
+// HTTP caching and compression for embedded assets
+// Every embedded file gets a strong ETag from the content hash rust-embed
+// computes at build time, and a Last-Modified of the build itself (the
+// binary is the only "disk" these files live on). Revalidations that still
+// match get an empty 304 instead of the full body.
+//
+// Text assets are served as build.rs left them: references to images, CSS and
+// JS point at fingerprinted URLs, which are cached forever, and brotli and
+// gzip variants are ready-made; the best one the client accepts is sent as-is,
+// with its own ETag.
+//
+// Documents the server generates at startup go through the same path, with
+// their variants compressed once when they're made.
+//
+// Byte ranges (single or multipart) are answered from slices of the embedded
+// bytes, so seeking in audio or resuming the APK download never copies the
+// asset.
+
+use actix_web::body::SizedStream;
+use actix_web::http::header::{self, EntityTag, HttpDate};
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder};
+use rust_embed::EmbeddedFile;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::io::Write as _;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// More ranges than this in one request are ignored and the full body is sent
+const MAX_RANGES: usize = 16;
+
+/// Unix time of the build, from build.rs
+const BUILD_TIME: &str = env!("SCC_BUILD_TIME");
+
+pub fn build_time() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(BUILD_TIME.parse().unwrap_or(0))
+}
+
+/// What build.rs made from one embedded text asset
+pub struct BuiltAsset {
+    path: &'static str,
+    source_sha256: [u8; 32],
+    /// The source with asset references rewritten to fingerprinted URLs, when
+    /// that changed anything
+    body: Option<&'static [u8]>,
+    /// SHA-256 of the body as served
+    sha256: [u8; 32],
+    br: Option<&'static [u8]>,
+    gzip: Option<&'static [u8]>,
+}
+
+impl BuiltAsset {
+    pub fn body(&self) -> Option<&'static [u8]> {
+        self.body
+    }
+
+    pub fn sha256(&self) -> &[u8; 32] {
+        &self.sha256
+    }
+
+    pub fn has_variants(&self) -> bool {
+        self.br.is_some() || self.gzip.is_some()
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/built_assets.rs"));
+
+/// Build output for the asset at `path`, if it was made from these exact
+/// bytes (debug builds read assets from disk, which may have changed since)
+pub fn built(path: &str, file: &EmbeddedFile) -> Option<&'static BuiltAsset> {
+    let index = BUILT_ASSETS.binary_search_by_key(&path, |asset| asset.path).ok()?;
+    let asset = &BUILT_ASSETS[index];
+    (asset.source_sha256 == file.metadata.sha256_hash()).then_some(asset)
+}
+
+/// Embedded path behind a fingerprinted URL ("css/style.min.<hash>.css")
+pub fn unfingerprinted(path: &str) -> Option<&'static str> {
+    let index = FINGERPRINTS.binary_search_by_key(&path, |(hashed, _)| hashed).ok()?;
+    Some(FINGERPRINTS[index].1)
+}
+
+/// Whether a fingerprinted URL still names the bytes being served: text
+/// assets only have build output while unchanged, other files carry the hash
+/// of their own content. A file replaced since the build (an overlay hotfix)
+/// must not be cached forever under the old URL.
+pub fn fingerprint_current(url_path: &str, built: Option<&BuiltAsset>, sha256: &[u8; 32]) -> bool {
+    let hex: String = sha256[..5].iter().map(|b| format!("{:02x}", b)).collect();
+    built.is_some() || url_path.contains(&format!(".{}.", hex))
+}
+
+/// Quality the Accept-Encoding header gives `coding` (0 when not acceptable)
+fn quality(accept_encoding: &str, coding: &str) -> f32 {
+    let mut wildcard = None;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or("").trim();
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if name.eq_ignore_ascii_case(coding) {
+            return q;
+        }
+        if name == "*" {
+            wildcard = Some(q);
+        }
+    }
+    wildcard.unwrap_or(0.0)
+}
+
+/// Best variant the client accepts as (content-coding, body): brotli unless
+/// the client prefers gzip, or None to send the original
+pub fn negotiate(req: &HttpRequest, variants: &BuiltAsset) -> Option<(&'static str, &'static [u8])> {
+    choose_variant(req, variants.br, variants.gzip)
+}
+
+fn choose_variant<T>(req: &HttpRequest, br: Option<T>, gzip: Option<T>) -> Option<(&'static str, T)> {
+    let accept = req.headers().get(header::ACCEPT_ENCODING)?.to_str().ok()?;
+    let br = br.map(|body| (quality(accept, "br"), "br", body));
+    let gzip = gzip.map(|body| (quality(accept, "gzip"), "gzip", body));
+    [br, gzip]
+        .into_iter()
+        .flatten()
+        .filter(|(q, _, _)| *q > 0.0)
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .map(|(_, coding, body)| (coding, body))
+}
+
+/// Strong validator from the first 128 bits of the body's SHA-256, suffixed
+/// with the content-coding for compressed variants
+pub fn etag(sha256: &[u8; 32], coding: Option<&str>) -> EntityTag {
+    let hash = sha256;
+    let mut tag: String = hash[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    if let Some(coding) = coding {
+        tag.push('-');
+        tag.push_str(coding);
+    }
+    EntityTag::new_strong(tag)
+}
+
+/// Whether the client's cached copy is still current. If-None-Match wins over
+/// If-Modified-Since when both are sent (RFC 9110 13.2.2).
+pub fn not_modified(req: &HttpRequest, etag: &EntityTag, last_modified: SystemTime) -> bool {
+    if req.headers().contains_key(header::IF_NONE_MATCH) {
+        return match req.get_header::<header::IfNoneMatch>() {
+            Some(header::IfNoneMatch::Any) => true,
+            Some(header::IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+            None => false,
+        };
+    }
+    match req.get_header::<header::IfModifiedSince>() {
+        Some(header::IfModifiedSince(since)) => since >= HttpDate::from(last_modified),
+        None => false,
+    }
+}
+
+/// Embedded bytes as a response body; release builds borrow them from the binary
+pub fn static_bytes(data: Cow<'static, [u8]>) -> Bytes {
+    match data {
+        Cow::Borrowed(bytes) => Bytes::from_static(bytes),
+        Cow::Owned(bytes) => Bytes::from(bytes),
+    }
+}
+
+/// A document generated once at startup (sitemap.xml, robots.txt), compressed
+/// then and served with the same validators and encodings as a built asset
+pub struct Generated {
+    body: Bytes,
+    sha256: [u8; 32],
+    br: Option<Bytes>,
+    gzip: Option<Bytes>,
+}
+
+impl Generated {
+    pub fn new(body: String) -> Self {
+        let body = Bytes::from(body);
+        let smaller = |data: Vec<u8>| (data.len() < body.len()).then(|| Bytes::from(data));
+        Generated {
+            sha256: Sha256::digest(&body).into(),
+            br: smaller(brotli(&body)),
+            gzip: smaller(gzip(&body)),
+            body,
+        }
+    }
+
+    pub fn respond(&self, req: &HttpRequest, content_type: &str, cache_control: &str) -> HttpResponse {
+        let encoded = choose_variant(req, self.br.as_ref(), self.gzip.as_ref());
+        let etag = etag(&self.sha256, encoded.map(|(coding, _)| coding));
+        let last_modified = build_time();
+        let not_modified = not_modified(req, &etag, last_modified);
+
+        let mut response = if not_modified { HttpResponse::NotModified() } else { HttpResponse::Ok() };
+        response
+            .insert_header((header::CACHE_CONTROL, cache_control))
+            .insert_header(header::ETag(etag.clone()))
+            .insert_header(header::LastModified(last_modified.into()));
+        if self.br.is_some() || self.gzip.is_some() {
+            response.insert_header((header::VARY, "Accept-Encoding"));
+        }
+        if not_modified {
+            return response.finish();
+        }
+
+        response.content_type(content_type);
+        let body = match encoded {
+            Some((coding, body)) => {
+                response.insert_header((header::CONTENT_ENCODING, coding));
+                body.clone()
+            }
+            None => {
+                if req.headers().contains_key(header::ACCEPT_ENCODING) {
+                    response.insert_header((header::CONTENT_ENCODING, "identity"));
+                }
+                self.body.clone()
+            }
+        };
+        ranged_body(req, response, body, &etag, last_modified, content_type)
+    }
+}
+
+/// Same settings build.rs compresses the embedded assets with
+fn brotli(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+        writer.write_all(data).expect("brotli compression failed");
+    }
+    out
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(data).expect("gzip compression failed");
+    encoder.finish().expect("gzip compression failed")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RangeRequest {
+    Full,
+    /// Sorted, non-overlapping inclusive byte ranges
+    Partial(Vec<(u64, u64)>),
+    Unsatisfiable,
+}
+
+/// What to send for a body of `len` bytes: the whole thing, some ranges of it,
+/// or a 416. A Range whose If-Range no longer matches gets the full body.
+pub fn requested_ranges(req: &HttpRequest, len: u64, etag: &EntityTag, last_modified: SystemTime) -> RangeRequest {
+    if !req.headers().contains_key(header::RANGE) {
+        return RangeRequest::Full;
+    }
+    if req.headers().contains_key(header::IF_RANGE) {
+        let current = match req.get_header::<header::IfRange>() {
+            Some(header::IfRange::EntityTag(tag)) => tag.strong_eq(etag),
+            Some(header::IfRange::Date(date)) => date == HttpDate::from(last_modified),
+            None => false,
+        };
+        if !current {
+            return RangeRequest::Full;
+        }
+    }
+    let specs = match req.get_header::<header::Range>() {
+        Some(header::Range::Bytes(specs)) if specs.len() <= MAX_RANGES => specs,
+        _ => return RangeRequest::Full,
+    };
+
+    let mut ranges: Vec<(u64, u64)> = specs.iter().filter_map(|spec| spec.to_satisfiable_range(len)).collect();
+    if ranges.is_empty() {
+        return RangeRequest::Unsatisfiable;
+    }
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    RangeRequest::Partial(merged)
+}
+
+/// Finish an asset response with the requested part of `body`
+pub fn ranged_body(
+    req: &HttpRequest,
+    mut response: HttpResponseBuilder,
+    body: Bytes,
+    etag: &EntityTag,
+    last_modified: SystemTime,
+    content_type: &str,
+) -> HttpResponse {
+    response.insert_header((header::ACCEPT_RANGES, "bytes"));
+    let len = body.len() as u64;
+    let ranges = match requested_ranges(req, len, etag, last_modified) {
+        RangeRequest::Full => return response.body(body),
+        RangeRequest::Unsatisfiable => {
+            return response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", len)))
+                .finish();
+        }
+        RangeRequest::Partial(ranges) => ranges,
+    };
+
+    response.status(StatusCode::PARTIAL_CONTENT);
+    if let [(start, end)] = ranges[..] {
+        return response
+            .insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)))
+            .body(body.slice(start as usize..=end as usize));
+    }
+
+    let boundary = uuid::Uuid::new_v4().simple().to_string();
+    let mut parts = Vec::with_capacity(ranges.len() * 2 + 1);
+    for (start, end) in ranges {
+        parts.push(Bytes::from(format!(
+            "\r\n--{}\r\nContent-Type: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+            boundary, content_type, start, end, len
+        )));
+        parts.push(body.slice(start as usize..=end as usize));
+    }
+    parts.push(Bytes::from(format!("\r\n--{}--\r\n", boundary)));
+
+    let size = parts.iter().map(|part| part.len() as u64).sum();
+    let stream = futures_util::stream::iter(parts.into_iter().map(Ok::<_, std::convert::Infallible>));
+    response
+        .insert_header((header::CONTENT_TYPE, format!("multipart/byteranges; boundary={}", boundary)))
+        .body(SizedStream::new(size, stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::AssetSource;
+    use actix_web::test::TestRequest;
+
+    fn tag() -> EntityTag {
+        EntityTag::new_strong("abc123".to_string())
+    }
+
+    fn build_date() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let matching = TestRequest::default().insert_header((header::IF_NONE_MATCH, "\"zzz\", \"abc123\"")).to_http_request();
+        assert!(not_modified(&matching, &tag(), build_date()));
+        let weak = TestRequest::default().insert_header((header::IF_NONE_MATCH, "W/\"abc123\"")).to_http_request();
+        assert!(not_modified(&weak, &tag(), build_date()));
+        let any = TestRequest::default().insert_header((header::IF_NONE_MATCH, "*")).to_http_request();
+        assert!(not_modified(&any, &tag(), build_date()));
+        let stale = TestRequest::default().insert_header((header::IF_NONE_MATCH, "\"old\"")).to_http_request();
+        assert!(!not_modified(&stale, &tag(), build_date()));
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let since = |time: SystemTime| {
+            TestRequest::default()
+                .insert_header((header::IF_MODIFIED_SINCE, HttpDate::from(time).to_string()))
+                .to_http_request()
+        };
+        assert!(not_modified(&since(build_date()), &tag(), build_date()));
+        assert!(!not_modified(&since(build_date() - Duration::from_secs(60)), &tag(), build_date()));
+        assert!(!not_modified(&TestRequest::default().to_http_request(), &tag(), build_date()));
+    }
+
+    #[test]
+    fn test_quality() {
+        assert_eq!(quality("gzip, deflate, br", "br"), 1.0);
+        assert_eq!(quality("gzip;q=0.8, br;q=0.5", "br"), 0.5);
+        assert_eq!(quality("gzip", "br"), 0.0);
+        assert_eq!(quality("*;q=0.3", "gzip"), 0.3);
+        assert_eq!(quality("br;q=0, *", "br"), 0.0);
+    }
+
+    #[test]
+    fn test_negotiate_prefers_brotli() {
+        let file = crate::assets::EmbeddedAssets.get("index.html").unwrap();
+        let variants = built("index.html", &file).expect("index.html should be precompressed");
+        let request = |accept: &str| TestRequest::default().insert_header((header::ACCEPT_ENCODING, accept)).to_http_request();
+        assert_eq!(negotiate(&request("gzip, deflate, br"), variants).map(|v| v.0), Some("br"));
+        assert_eq!(negotiate(&request("gzip"), variants).map(|v| v.0), Some("gzip"));
+        assert_eq!(negotiate(&request("br;q=0.5, gzip"), variants).map(|v| v.0), Some("gzip"));
+        assert!(negotiate(&request("identity"), variants).is_none());
+        assert!(negotiate(&TestRequest::default().to_http_request(), variants).is_none());
+        let (_, body) = negotiate(&request("br"), variants).unwrap();
+        assert!(body.len() < file.data.len());
+    }
+
+    #[actix_web::test]
+    async fn test_generated_precompressed() {
+        let document = Generated::new("<urlset>\n".repeat(200));
+        let req = TestRequest::default().insert_header((header::ACCEPT_ENCODING, "gzip")).to_http_request();
+        let response = document.respond(&req, "application/xml", "public, max-age=3600");
+        assert_eq!(response.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+        let etag = response.headers().get(header::ETAG).unwrap().to_str().unwrap().to_string();
+        assert!(etag.ends_with("-gzip\""));
+        let bytes = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(bytes.len() < document.body.len());
+
+        let revalidate = TestRequest::default()
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_http_request();
+        assert_eq!(document.respond(&revalidate, "application/xml", "public, max-age=3600").status(), StatusCode::NOT_MODIFIED);
+    }
+
+    fn range_request(range: &str) -> HttpRequest {
+        TestRequest::default().insert_header((header::RANGE, range)).to_http_request()
+    }
+
+    #[test]
+    fn test_requested_ranges() {
+        assert_eq!(requested_ranges(&TestRequest::default().to_http_request(), 100, &tag(), build_date()), RangeRequest::Full);
+        assert_eq!(requested_ranges(&range_request("bytes=0-9"), 100, &tag(), build_date()), RangeRequest::Partial(vec![(0, 9)]));
+        assert_eq!(requested_ranges(&range_request("bytes=-10"), 100, &tag(), build_date()), RangeRequest::Partial(vec![(90, 99)]));
+        assert_eq!(requested_ranges(&range_request("bytes=95-"), 100, &tag(), build_date()), RangeRequest::Partial(vec![(95, 99)]));
+        assert_eq!(
+            requested_ranges(&range_request("bytes=50-59, 0-9, 5-19, 20-24"), 100, &tag(), build_date()),
+            RangeRequest::Partial(vec![(0, 24), (50, 59)])
+        );
+        assert_eq!(requested_ranges(&range_request("bytes=200-300"), 100, &tag(), build_date()), RangeRequest::Unsatisfiable);
+        assert_eq!(requested_ranges(&range_request("lines=1-2"), 100, &tag(), build_date()), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let with_if_range = |value: String| {
+            TestRequest::default()
+                .insert_header((header::RANGE, "bytes=0-9"))
+                .insert_header((header::IF_RANGE, value))
+                .to_http_request()
+        };
+        let partial = RangeRequest::Partial(vec![(0, 9)]);
+        assert_eq!(requested_ranges(&with_if_range("\"abc123\"".to_string()), 100, &tag(), build_date()), partial);
+        assert_eq!(requested_ranges(&with_if_range("\"old\"".to_string()), 100, &tag(), build_date()), RangeRequest::Full);
+        assert_eq!(requested_ranges(&with_if_range(HttpDate::from(build_date()).to_string()), 100, &tag(), build_date()), partial);
+        let earlier = HttpDate::from(build_date() - Duration::from_secs(60)).to_string();
+        assert_eq!(requested_ranges(&with_if_range(earlier), 100, &tag(), build_date()), RangeRequest::Full);
+    }
+
+    #[actix_web::test]
+    async fn test_multipart_ranges_body() {
+        let req = range_request("bytes=0-1, 6-7");
+        let body = Bytes::from_static(b"0123456789");
+        let response = ranged_body(&req, HttpResponse::Ok(), body, &tag(), build_date(), "audio/mpeg");
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        let content_type = response.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap().to_string();
+        let boundary = content_type.strip_prefix("multipart/byteranges; boundary=").unwrap().to_string();
+        let bytes = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains("Content-Range: bytes 0-1/10\r\n\r\n01\r\n"));
+        assert!(text.contains("Content-Range: bytes 6-7/10\r\n\r\n67\r\n"));
+        assert!(text.ends_with(&format!("--{}--\r\n", boundary)));
+    }
+
+    #[test]
+    fn test_fingerprinted_urls() {
+        let file = crate::assets::EmbeddedAssets.get("index.html").unwrap();
+        let body = std::str::from_utf8(built("index.html", &file).unwrap().body().unwrap()).unwrap();
+        let start = body.find("css/style.min.").unwrap();
+        let hashed = &body[start..start + body[start..].find('"').unwrap()];
+        assert_ne!(hashed, "css/style.min.css");
+        assert_eq!(unfingerprinted(hashed), Some("css/style.min.css"));
+        assert_eq!(unfingerprinted("css/style.min.css"), None);
+    }
+
+    #[test]
+    fn test_fingerprint_current() {
+        let sha256 = [0xab; 32];
+        assert!(fingerprint_current("images/logo.ababababab.webp", None, &sha256));
+        assert!(!fingerprint_current("images/logo.0123456789.webp", None, &sha256));
+    }
+
+    #[test]
+    fn test_etag_mismatch_overrides_date() {
+        let req = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, "\"old\""))
+            .insert_header((header::IF_MODIFIED_SINCE, HttpDate::from(build_date()).to_string()))
+            .to_http_request();
+        assert!(!not_modified(&req, &tag(), build_date()));
+    }
+}
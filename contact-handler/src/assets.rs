@@ -0,0 +1,207 @@
+// Static asset sources
+// Pages, CSS, JS, images and audio are looked up through an AssetSource.
+// scc-server and scc-desktop serve the files embedded at build time; scc-dev
+// reads the same set of files from its static directory on every request, so
+// edits show up on the next refresh while every handler, header and
+// negotiation rule stays the production one.
+//
+// Both sources pick files with the same include/exclude patterns, so the dev
+// server never serves anything (CSV stores, accounts.txt, sources) the
+// production binary wouldn't have embedded.
+
+use rust_embed::{EmbeddedFile, RustEmbed};
+use rust_embed_utils::PathMatcher;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::asset_http;
+
+#[derive(RustEmbed)]
+#[folder = "../"]
+#[include = "*.html"]
+#[include = "services/*.html"]
+#[include = "blog/*.html"]
+#[include = "admin/*.html"]
+#[include = "phpmyadmin/*.html"]
+#[include = "css/*.min.css"]
+#[include = "js/*.min.js"]
+#[include = "images/*.webp"]
+#[include = "images/*.ico"]
+#[include = "images/*.png"]
+#[include = "images/backgrounds/*.webp"]
+#[include = "audio/*.mp3"]
+#[include = "app/*"]
+#[exclude = "contact-handler/*"]
+#[exclude = "android-app/*"]
+#[exclude = "downloaded/*"]
+#[exclude = "scripts/*"]
+#[exclude = "*.md"]
+#[exclude = "*.txt"]
+#[exclude = "*.yml"]
+#[exclude = "Dockerfile"]
+struct Embedded;
+
+/// The patterns on `Embedded`, for sources that read from disk
+const INCLUDE: &[&str] = &[
+    "*.html",
+    "services/*.html",
+    "blog/*.html",
+    "admin/*.html",
+    "phpmyadmin/*.html",
+    "css/*.min.css",
+    "js/*.min.js",
+    "images/*.webp",
+    "images/*.ico",
+    "images/*.png",
+    "images/backgrounds/*.webp",
+    "audio/*.mp3",
+    "app/*",
+];
+const EXCLUDE: &[&str] = &["contact-handler/*", "android-app/*", "downloaded/*", "scripts/*", "*.md", "*.txt", "*.yml", "Dockerfile"];
+
+/// Cache-Control for files read from disk: always revalidate
+const DISK_CACHE: &str = "no-cache";
+
+pub trait AssetSource: Send + Sync {
+    /// File at `path` (relative, "/"-separated)
+    fn get(&self, path: &str) -> Option<EmbeddedFile>;
+
+    /// Every path this source serves
+    fn paths(&self) -> Vec<String>;
+
+    /// Last-Modified to send for a file from this source
+    fn last_modified(&self, file: &EmbeddedFile) -> SystemTime;
+
+    /// Cache-Control for every file, replacing the per-path policy
+    fn cache_control(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// A source that lives for the whole run can be shared as-is
+impl<T: AssetSource + ?Sized> AssetSource for &'static T {
+    fn get(&self, path: &str) -> Option<EmbeddedFile> {
+        (**self).get(path)
+    }
+
+    fn paths(&self) -> Vec<String> {
+        (**self).paths()
+    }
+
+    fn last_modified(&self, file: &EmbeddedFile) -> SystemTime {
+        (**self).last_modified(file)
+    }
+
+    fn cache_control(&self) -> Option<&'static str> {
+        (**self).cache_control()
+    }
+}
+
+/// The files built into the binary
+pub struct EmbeddedAssets;
+
+impl AssetSource for EmbeddedAssets {
+    fn get(&self, path: &str) -> Option<EmbeddedFile> {
+        Embedded::get(path)
+    }
+
+    fn paths(&self) -> Vec<String> {
+        Embedded::iter().map(|path| path.into_owned()).collect()
+    }
+
+    /// The binary is the only "disk" these files live on
+    fn last_modified(&self, _file: &EmbeddedFile) -> SystemTime {
+        asset_http::build_time()
+    }
+}
+
+/// The same files, read from a directory on every request
+pub struct DiskAssets {
+    root: PathBuf,
+    matcher: PathMatcher,
+}
+
+impl DiskAssets {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        DiskAssets { root: root.into(), matcher: PathMatcher::new(INCLUDE, EXCLUDE) }
+    }
+
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Whether `path` names a file the embedded build would include; never
+    /// anything outside the root
+    fn serves(&self, path: &str) -> bool {
+        !path.contains('\\')
+            && path.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+            && self.matcher.is_path_included(path)
+    }
+}
+
+impl AssetSource for DiskAssets {
+    fn get(&self, path: &str) -> Option<EmbeddedFile> {
+        if !self.serves(path) {
+            return None;
+        }
+        rust_embed_utils::read_file_from_fs(&self.root.join(path)).ok()
+    }
+
+    fn paths(&self) -> Vec<String> {
+        let root = self.root.to_string_lossy().into_owned();
+        rust_embed_utils::get_files(root, self.matcher.clone()).map(|entry| entry.rel_path).collect()
+    }
+
+    fn last_modified(&self, file: &EmbeddedFile) -> SystemTime {
+        file.metadata.last_modified().map(|secs| UNIX_EPOCH + Duration::from_secs(secs)).unwrap_or_else(SystemTime::now)
+    }
+
+    fn cache_control(&self) -> Option<&'static str> {
+        Some(DISK_CACHE)
+    }
+}
+
+static SOURCE: OnceLock<Box<dyn AssetSource>> = OnceLock::new();
+
+/// Serve assets from `source` instead of the embedded files. Call before the
+/// server starts; later calls are ignored.
+pub fn init(source: impl AssetSource + 'static) {
+    let _ = SOURCE.set(Box::new(source));
+}
+
+/// Where assets come from: the embedded files unless a binary chose otherwise
+pub fn source() -> &'static dyn AssetSource {
+    SOURCE.get_or_init(|| Box::new(EmbeddedAssets)).as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site_root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+    }
+
+    #[test]
+    fn test_disk_serves_what_is_embedded() {
+        let disk = DiskAssets::new(site_root());
+        let mut on_disk = disk.paths();
+        let mut embedded = EmbeddedAssets.paths();
+        on_disk.sort();
+        embedded.sort();
+        assert_eq!(on_disk, embedded);
+
+        let page = disk.get("index.html").unwrap();
+        assert_eq!(page.metadata.sha256_hash(), EmbeddedAssets.get("index.html").unwrap().metadata.sha256_hash());
+    }
+
+    #[test]
+    fn test_disk_refuses_other_files() {
+        let disk = DiskAssets::new(site_root());
+        for path in ["contact-handler/Cargo.toml", "README.md", "../README.md", "css/../index.html", "/index.html", "docker-compose.yml", "css/style.css"] {
+            assert!(disk.get(path).is_none(), "{} should not be served", path);
+        }
+        assert!(disk.get("css/style.min.css").is_some());
+    }
+}
@@ -177,7 +177,7 @@ fn print_results(name: &str, results: &BenchmarkResults, duration: Duration) {
     let max_us = results.max_latency_us.load(Ordering::Relaxed);
     let total_latency = results.total_latency_us.load(Ordering::Relaxed);
 
-    let avg_latency = if successful > 0 { total_latency / successful } else { 0 };
+    let avg_latency = total_latency.checked_div(successful).unwrap_or(0);
     let throughput = if duration.as_secs_f64() > 0.0 {
         successful as f64 / duration.as_secs_f64()
     } else {
@@ -0,0 +1,826 @@
+// Quotes and invoices
+// An admin turns a service inquiry into a quote (labor/parts line items, MXN
+// or USD, IVA, a validity date) and later converts the quote into an invoice.
+// Both live in append-only CSV stores next to the other records; invoice
+// numbers are sequential and taken from invoices.csv, so they survive
+// restarts. PDFs are bilingual, Spanish first, like our printed paperwork.
+
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::intake::title_case;
+use crate::pdf_layout::{Align, PageStyle, PdfLayout, PdfResult, Span, Weight};
+use crate::lang::{self, Lang};
+use crate::{check_auth, find_service_inquiry_by_id, generate_short_id, html_escape, records, security_headers};
+
+pub const DEFAULT_TAX_RATE: f64 = 16.0;
+pub const DEFAULT_VALID_DAYS: i64 = 15;
+pub const INVOICE_DUE_DAYS: i64 = 15;
+const INVOICE_PREFIX: &str = "SCC";
+
+/// Serializes "read highest number, append next" so two requests can't
+/// claim the same invoice number.
+static INVOICE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[default]
+    Mxn,
+    Usd,
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Mxn => "MXN",
+            Currency::Usd => "USD",
+        }
+    }
+
+    pub fn parse(code: &str) -> Currency {
+        if code.eq_ignore_ascii_case("USD") { Currency::Usd } else { Currency::Mxn }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    #[default]
+    Labor,
+    Parts,
+    Other,
+}
+
+impl ItemKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ItemKind::Labor => "Mano de obra / Labor",
+            ItemKind::Parts => "Refacciones / Parts",
+            ItemKind::Other => "Otro / Other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineItem {
+    #[serde(default)]
+    pub kind: ItemKind,
+    pub description: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+}
+
+impl LineItem {
+    pub fn total_cents(&self) -> i64 {
+        (self.quantity * self.unit_price * 100.0).round() as i64
+    }
+}
+
+pub struct Totals {
+    pub subtotal: i64,
+    pub tax: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub id: String,
+    pub created: String,
+    pub inquiry_id: String,
+    pub service_type: String,
+    pub name: String,
+    pub email: String,
+    pub phone: String,
+    pub currency: Currency,
+    /// IVA as a percentage, e.g. 16 (8 in the northern border zone, 0 for exports)
+    pub tax_rate: f64,
+    pub valid_until: String,
+    pub items: Vec<LineItem>,
+    pub notes: String,
+}
+
+impl Quote {
+    pub fn totals(&self) -> Totals {
+        let subtotal = self.items.iter().map(LineItem::total_cents).sum();
+        let tax = (subtotal as f64 * self.tax_rate / 100.0).round() as i64;
+        Totals { subtotal, tax, total: subtotal + tax }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub number: u32,
+    pub issued: String,
+    pub due: String,
+    pub quote_id: String,
+}
+
+impl Invoice {
+    pub fn label(&self) -> String {
+        format!("{}-{:05}", INVOICE_PREFIX, self.number)
+    }
+}
+
+/// "$1,234.50 MXN"
+pub fn format_money(cents: i64, currency: Currency) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, c) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}${}.{:02} {}", sign, grouped, cents % 100, currency.code())
+}
+
+fn format_quantity(quantity: f64) -> String {
+    if quantity.fract() == 0.0 { format!("{}", quantity as i64) } else { format!("{:.2}", quantity) }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteRequest {
+    pub inquiry_id: String,
+    #[serde(default)]
+    pub currency: Currency,
+    pub tax_rate: Option<f64>,
+    pub valid_days: Option<i64>,
+    pub items: Vec<LineItem>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BillingResponse {
+    success: bool,
+    message: String,
+    /// Quote id, or the invoice number when converting
+    id: Option<String>,
+}
+
+impl BillingResponse {
+    fn ok(message: &str, id: String) -> HttpResponse {
+        HttpResponse::Ok().json(BillingResponse { success: true, message: message.to_string(), id: Some(id) })
+    }
+
+    fn error(status: actix_web::http::StatusCode, message: &str) -> HttpResponse {
+        HttpResponse::build(status).json(BillingResponse { success: false, message: message.to_string(), id: None })
+    }
+}
+
+/// Check a quote request against its inquiry and build the quote
+pub fn build_quote(request: &QuoteRequest, inquiry: &crate::ServiceInquiryRecord) -> Result<Quote, String> {
+    if request.items.is_empty() {
+        return Err("A quote needs at least one line item".to_string());
+    }
+    for item in &request.items {
+        if item.description.trim().is_empty() {
+            return Err("Every line item needs a description".to_string());
+        }
+        if !(item.quantity > 0.0 && item.quantity.is_finite()) {
+            return Err(format!("Invalid quantity for \"{}\"", item.description));
+        }
+        if !(item.unit_price >= 0.0 && item.unit_price.is_finite()) {
+            return Err(format!("Invalid unit price for \"{}\"", item.description));
+        }
+    }
+    let tax_rate = request.tax_rate.unwrap_or(DEFAULT_TAX_RATE);
+    if !(0.0..=100.0).contains(&tax_rate) {
+        return Err("Tax rate must be between 0 and 100".to_string());
+    }
+    let valid_days = request.valid_days.unwrap_or(DEFAULT_VALID_DAYS);
+    if !(1..=365).contains(&valid_days) {
+        return Err("Validity must be between 1 and 365 days".to_string());
+    }
+
+    let now = Local::now();
+    Ok(Quote {
+        id: generate_short_id(),
+        created: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        inquiry_id: inquiry.id.clone(),
+        service_type: inquiry.service_type.clone(),
+        name: inquiry.name.clone(),
+        email: inquiry.email.clone(),
+        phone: inquiry.phone.clone(),
+        currency: request.currency,
+        tax_rate,
+        valid_until: (now.date_naive() + Duration::days(valid_days)).format("%Y-%m-%d").to_string(),
+        items: request
+            .items
+            .iter()
+            .map(|item| LineItem { description: item.description.trim().to_string(), ..item.clone() })
+            .collect(),
+        notes: request.notes.trim().to_string(),
+    })
+}
+
+fn find_quote(id: &str) -> Option<Quote> {
+    records::load_quotes().into_iter().find(|q| q.id == id)
+}
+
+/// Invoice a quote. Converting the same quote twice returns the existing
+/// invoice rather than using up another number.
+pub fn invoice_quote(quote: &Quote) -> std::io::Result<Invoice> {
+    let _guard = INVOICE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let invoices = records::load_invoices();
+    if let Some(existing) = invoices.iter().find(|inv| inv.quote_id == quote.id) {
+        return Ok(existing.clone());
+    }
+
+    let today = Local::now().date_naive();
+    let invoice = Invoice {
+        number: invoices.iter().map(|inv| inv.number).max().unwrap_or(0) + 1,
+        issued: today.format("%Y-%m-%d").to_string(),
+        due: (today + Duration::days(INVOICE_DUE_DAYS)).format("%Y-%m-%d").to_string(),
+        quote_id: quote.id.clone(),
+    };
+    records::append_invoice(&invoice)?;
+    Ok(invoice)
+}
+
+fn bold(text: &str) -> Vec<Span> {
+    vec![Span::new(text, Weight::Bold)]
+}
+
+fn plain(text: &str) -> Vec<Span> {
+    vec![Span::new(text, Weight::Regular)]
+}
+
+/// Quote PDF, or the invoice PDF when `invoice` is given
+pub fn generate_billing_pdf(quote: &Quote, invoice: Option<&Invoice>) -> PdfResult<Vec<u8>> {
+    let (title, subtitle) = match invoice {
+        Some(invoice) => (format!("Invoice {}", invoice.label()), "Factura / Invoice"),
+        None => (format!("Quote {}", quote.id), "Cotización / Quote"),
+    };
+    let mut pdf = PdfLayout::new(&title, PageStyle {
+        header_title: "SOUTH CITY COMPUTER".to_string(),
+        header_subtitle: subtitle.to_string(),
+        footer: "South City Computer | Puerto Morelos, Mexico | southcitycomputer.com".to_string(),
+        page_label: "Página / Page".to_string(),
+    })?;
+    let reference = invoice.map(|invoice| invoice.label()).unwrap_or_else(|| quote.id.clone());
+    pdf.qr_code(&reference, &reference)?;
+
+    let mut details = Vec::new();
+    if let Some(invoice) = invoice {
+        details.push(vec![bold("Factura / Invoice"), plain(&invoice.label())]);
+        details.push(vec![bold("Fecha / Date"), plain(&invoice.issued)]);
+        details.push(vec![bold("Vencimiento / Due"), plain(&invoice.due)]);
+    }
+    details.push(vec![bold("Cotización / Quote"), plain(&quote.id)]);
+    if invoice.is_none() {
+        details.push(vec![bold("Fecha / Date"), plain(quote.created.split(' ').next().unwrap_or(""))]);
+        details.push(vec![bold("Válida hasta / Valid until"), plain(&quote.valid_until)]);
+    }
+    details.push(vec![bold("Servicio / Service"), plain(&title_case(&quote.service_type))]);
+    details.push(vec![bold("Moneda / Currency"), plain(quote.currency.code())]);
+    pdf.table(&details, 0, &[], 9.0, 0.0);
+    pdf.space(6.0);
+
+    pdf.heading("Cliente / Customer");
+    pdf.text(&quote.name, 11.0, Weight::Bold);
+    pdf.text(&quote.email, 10.0, Weight::Regular);
+    if !quote.phone.is_empty() {
+        pdf.text(&quote.phone, 10.0, Weight::Regular);
+    }
+    pdf.space(6.0);
+
+    pdf.heading("Conceptos / Items");
+    let mut rows = vec![vec![
+        plain("Descripción / Description"),
+        plain("Tipo / Type"),
+        plain("Cant. / Qty"),
+        plain("P. unitario / Unit price"),
+        plain("Importe / Amount"),
+    ]];
+    for item in &quote.items {
+        rows.push(vec![
+            plain(&item.description),
+            plain(item.kind.label()),
+            plain(&format_quantity(item.quantity)),
+            plain(&format_money((item.unit_price * 100.0).round() as i64, quote.currency)),
+            plain(&format_money(item.total_cents(), quote.currency)),
+        ]);
+    }
+    let right = [Align::Left, Align::Left, Align::Right, Align::Right, Align::Right];
+    pdf.table(&rows, 1, &right, 9.0, 0.0);
+    pdf.space(4.0);
+
+    let totals = quote.totals();
+    let summary = vec![
+        vec![plain("Subtotal"), plain(&format_money(totals.subtotal, quote.currency))],
+        vec![plain(&format!("IVA {}%", format_quantity(quote.tax_rate))), plain(&format_money(totals.tax, quote.currency))],
+        vec![bold("Total"), bold(&format_money(totals.total, quote.currency))],
+    ];
+    let indent = pdf.content_width() - 75.0;
+    pdf.table(&summary, 0, &[Align::Left, Align::Right], 10.0, indent);
+    pdf.space(8.0);
+
+    if !quote.notes.is_empty() {
+        pdf.heading("Notas / Notes");
+        pdf.text(&quote.notes, 10.0, Weight::Regular);
+        pdf.space(6.0);
+    }
+
+    match invoice {
+        Some(invoice) => {
+            pdf.text(
+                &format!("Pago a más tardar el {}. / Payment due by {}.", invoice.due, invoice.due),
+                9.0,
+                Weight::Regular,
+            );
+            pdf.text(
+                "Documento sin validez fiscal; solicite su CFDI si lo requiere. / Not a tax receipt (CFDI); ask us if you need one.",
+                8.0,
+                Weight::Regular,
+            );
+        }
+        None => pdf.text(
+            &format!(
+                "Esta cotización es válida hasta el {}. / This quote is valid until {}.",
+                quote.valid_until, quote.valid_until
+            ),
+            9.0,
+            Weight::Regular,
+        ),
+    }
+
+    pdf.finish()
+}
+
+pub fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .insert_header(("WWW-Authenticate", "Basic realm=\"Contact Admin\""))
+        .body("Unauthorized")
+}
+
+/// An admin page in the negotiated language
+pub fn admin_page(lang: Lang, html: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CONTENT_LANGUAGE, lang.code()))
+        .insert_header((header::VARY, "Accept-Language, Cookie"))
+        .body(html)
+}
+
+/// JSON string literal for a page script, with '<' escaped so it can't close
+/// the script
+fn js_string(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string().replace('<', "\\u003c")
+}
+
+fn pdf_response(result: PdfResult<Vec<u8>>, filename: &str) -> HttpResponse {
+    match result {
+        Ok(pdf_bytes) => HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+            .body(pdf_bytes),
+        Err(e) => {
+            log::error!("PDF generation error: {}", e);
+            HttpResponse::InternalServerError().body("Failed to generate PDF")
+        }
+    }
+}
+
+async fn create_quote(req: HttpRequest, body: web::Bytes) -> HttpResponse {
+    use actix_web::http::StatusCode;
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    // Parsed after the auth check so anonymous callers only ever see a 401
+    let request: QuoteRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return BillingResponse::error(StatusCode::BAD_REQUEST, &format!("Invalid quote: {}", e)),
+    };
+    let Some(inquiry) = find_service_inquiry_by_id(&request.inquiry_id) else {
+        return BillingResponse::error(StatusCode::NOT_FOUND, "Service inquiry not found");
+    };
+    let quote = match build_quote(&request, &inquiry) {
+        Ok(quote) => quote,
+        Err(message) => return BillingResponse::error(StatusCode::BAD_REQUEST, &message),
+    };
+    match records::append_quote(&quote) {
+        Ok(_) => {
+            log::info!("Quote created: {} for inquiry {}", quote.id, quote.inquiry_id);
+            BillingResponse::ok("Quote created", quote.id)
+        }
+        Err(e) => {
+            log::error!("Error writing quote to CSV: {}", e);
+            BillingResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to save quote")
+        }
+    }
+}
+
+async fn convert_to_invoice(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    use actix_web::http::StatusCode;
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let Some(quote) = find_quote(&path.into_inner()) else {
+        return BillingResponse::error(StatusCode::NOT_FOUND, "Quote not found");
+    };
+    match invoice_quote(&quote) {
+        Ok(invoice) => {
+            log::info!("Invoice {} issued for quote {}", invoice.label(), quote.id);
+            BillingResponse::ok("Invoice issued", invoice.number.to_string())
+        }
+        Err(e) => {
+            log::error!("Error writing invoice to CSV: {}", e);
+            BillingResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to save invoice")
+        }
+    }
+}
+
+async fn quote_pdf(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    match find_quote(&path.into_inner()) {
+        Some(quote) => pdf_response(generate_billing_pdf(&quote, None), &format!("quote-{}.pdf", quote.id)),
+        None => HttpResponse::NotFound().body("Quote not found"),
+    }
+}
+
+async fn invoice_pdf(req: HttpRequest, path: web::Path<u32>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let number = path.into_inner();
+    let invoice = records::load_invoices().into_iter().find(|inv| inv.number == number);
+    match invoice.and_then(|inv| find_quote(&inv.quote_id).map(|quote| (inv, quote))) {
+        Some((invoice, quote)) => {
+            pdf_response(generate_billing_pdf(&quote, Some(&invoice)), &format!("invoice-{}.pdf", invoice.label()))
+        }
+        None => HttpResponse::NotFound().body("Invoice not found"),
+    }
+}
+
+pub const ADMIN_STYLE: &str = r#"
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; background: #1a1a1a; color: #fff; min-height: 100vh; padding: 20px; }
+        .container { max-width: 1100px; margin: 0 auto; }
+        h1, h2 { margin-bottom: 24px; background: linear-gradient(135deg, #e91e8c, #00bcd4); -webkit-background-clip: text; -webkit-text-fill-color: transparent; background-clip: text; }
+        h2 { margin-top: 32px; }
+        table { width: 100%; border-collapse: collapse; background: #333; border-radius: 8px; overflow: hidden; margin-bottom: 24px; }
+        th, td { padding: 12px 16px; text-align: left; border-bottom: 1px solid #444; }
+        th { background: linear-gradient(135deg, #e91e8c, #00bcd4); font-weight: 600; text-transform: uppercase; font-size: 0.75rem; letter-spacing: 0.5px; }
+        td.num { text-align: right; font-variant-numeric: tabular-nums; }
+        a { color: #00bcd4; text-decoration: none; }
+        a:hover { text-decoration: underline; }
+        .back-link { display: inline-block; margin-bottom: 24px; color: #888; }
+        .card { background: #333; border-radius: 8px; padding: 20px; margin-bottom: 24px; }
+        label { display: block; color: #888; font-size: 0.8rem; margin-bottom: 4px; }
+        input, select, textarea { width: 100%; padding: 8px; border-radius: 4px; border: 1px solid #555; background: #2a2a2a; color: #fff; font: inherit; }
+        .row { display: flex; gap: 16px; margin-bottom: 16px; }
+        .row > div { flex: 1; }
+        button { padding: 8px 16px; border: none; border-radius: 4px; cursor: pointer; font: inherit; color: #fff; background: linear-gradient(135deg, #e91e8c, #00bcd4); }
+        button.secondary { background: #555; }
+        .message { margin-top: 12px; color: #ff6b6b; }
+"#;
+
+async fn quotes_admin(req: HttpRequest) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let quotes = records::load_quotes();
+    let invoices = records::load_invoices();
+    let lang = lang::negotiate(&req);
+    let t = |en, es| lang.pick(en, es);
+
+    let rows = if quotes.is_empty() {
+        format!(
+            "<tr><td colspan=\"7\" style=\"text-align: center; padding: 40px; color: #888;\">{}</td></tr>",
+            t(
+                "No quotes yet. Create one from a service inquiry in the admin panel.",
+                "Aún no hay cotizaciones. Crea una desde una solicitud de servicio en el panel."
+            )
+        )
+    } else {
+        quotes
+            .iter()
+            .rev()
+            .map(|quote| {
+                let id = html_escape(&quote.id);
+                let invoice_cell = match invoices.iter().find(|inv| inv.quote_id == quote.id) {
+                    Some(inv) => format!(
+                        "<a href=\"/contact-admin/invoices/{}/pdf\">{}</a> ({} {})",
+                        inv.number, inv.label(), t("due", "vence"), html_escape(&inv.due)
+                    ),
+                    None => format!(
+                        "<button class=\"invoice\" data-quote=\"{}\">{}</button>",
+                        id,
+                        t("Convert to invoice", "Convertir en factura")
+                    ),
+                };
+                format!(
+                    "<tr><td><a href=\"/contact-admin/quotes/{}/pdf\">{}</a></td><td>{}</td><td>{}<br><a href=\"/view/{}\">{}</a></td><td>{}</td><td class=\"num\">{}</td><td>{}</td><td>{}</td></tr>",
+                    id, id, html_escape(&quote.created), html_escape(&quote.name),
+                    html_escape(&quote.inquiry_id), html_escape(&quote.inquiry_id),
+                    html_escape(&title_case(&quote.service_type)),
+                    format_money(quote.totals().total, quote.currency),
+                    html_escape(&quote.valid_until), invoice_cell
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    admin_page(lang, format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>{style}</style>
+</head>
+<body>
+    <div class="container">
+        <a href="/contact-admin" class="back-link">&larr; {t_back}</a>
+        <h1>{t_title}</h1>
+        <table>
+            <thead>
+                <tr><th>{t_quote}</th><th>{t_created}</th><th>{t_customer}</th><th>{t_service}</th><th>Total</th><th>{t_valid}</th><th>{t_invoice}</th></tr>
+            </thead>
+            <tbody>
+                {rows}
+            </tbody>
+        </table>
+    </div>
+    <script nonce="{nonce}">
+        async function invoice(id) {{
+            if (!confirm({confirm_json}.replace('{{id}}', id))) return;
+            const response = await fetch('/api/quotes/' + id + '/invoice', {{ method: 'POST' }});
+            const result = await response.json();
+            if (result.success) {{ location.reload(); }} else {{ alert(result.message); }}
+        }}
+        document.querySelectorAll('button.invoice').forEach(function(button) {{
+            button.addEventListener('click', function() {{ invoice(button.dataset.quote); }});
+        }});
+    </script>
+</body>
+</html>"#,
+        lang = lang.code(),
+        style = ADMIN_STYLE,
+        rows = rows,
+        nonce = security_headers::nonce(&req),
+        confirm_json = js_string(t("Issue the next invoice number for quote {id}?", "¿Emitir el siguiente número de factura para la cotización {id}?")),
+        t_title = t("Quotes &amp; Invoices", "Cotizaciones y facturas"),
+        t_back = t("Back to Admin", "Volver al panel"),
+        t_quote = t("Quote", "Cotización"),
+        t_created = t("Created", "Creada"),
+        t_customer = t("Customer / Inquiry", "Cliente / Solicitud"),
+        t_service = t("Service", "Servicio"),
+        t_valid = t("Valid Until", "Válida hasta"),
+        t_invoice = t("Invoice", "Factura"),
+    ))
+}
+
+async fn new_quote_form(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let lang = lang::negotiate(&req);
+    let t = |en, es| lang.pick(en, es);
+    let Some(inquiry) = find_service_inquiry_by_id(&path.into_inner()) else {
+        return HttpResponse::NotFound().body(t("Service inquiry not found", "Solicitud de servicio no encontrada"));
+    };
+    let service = title_case(&inquiry.service_type);
+    let kinds = format!(
+        r#"<option value="labor">{}</option><option value="parts">{}</option><option value="other">{}</option>"#,
+        t("Labor", "Mano de obra"),
+        t("Parts", "Refacciones"),
+        t("Other", "Otro")
+    );
+
+    admin_page(lang, format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>{style}</style>
+</head>
+<body>
+    <div class="container">
+        <a href="/contact-admin" class="back-link">&larr; {t_back}</a>
+        <h1>{t_title}</h1>
+        <div class="card">
+            <strong>{name}</strong> &middot; <a href="mailto:{email}">{email}</a> &middot; {phone}<br>
+            {service} &middot; {t_inquiry} <a href="/view/{id}">{id}</a>
+        </div>
+        <form id="quote-form" class="card">
+            <div class="row">
+                <div><label for="currency">{t_currency}</label><select id="currency"><option>MXN</option><option>USD</option></select></div>
+                <div><label for="tax-rate">IVA %</label><input id="tax-rate" type="number" min="0" max="100" step="0.01" value="{tax}"></div>
+                <div><label for="valid-days">{t_valid_days}</label><input id="valid-days" type="number" min="1" max="365" value="{days}"></div>
+            </div>
+            <table>
+                <thead><tr><th>{t_type}</th><th>{t_description}</th><th>{t_qty}</th><th>{t_unit_price}</th><th></th></tr></thead>
+                <tbody id="items"></tbody>
+            </table>
+            <div class="row">
+                <div><button type="button" class="secondary" id="add-item">+ {t_add_item}</button></div>
+                <div style="text-align: right;" id="totals"></div>
+            </div>
+            <label for="notes">{t_notes}</label>
+            <textarea id="notes" rows="3"></textarea>
+            <div class="row" style="margin-top: 16px;"><div><button type="submit">{t_create}</button></div></div>
+            <div class="message" id="message"></div>
+        </form>
+    </div>
+    <script nonce="{nonce}">
+        const items = document.getElementById('items');
+        function addItem(kind, description) {{
+            const row = document.createElement('tr');
+            row.innerHTML = '<td><select class="kind">' + {kinds_json} + '</select></td>' +
+                '<td><input class="description"></td>' +
+                '<td><input class="quantity" type="number" min="0.01" step="0.01" value="1"></td>' +
+                '<td><input class="unit-price" type="number" min="0" step="0.01" value="0"></td>' +
+                '<td><button type="button" class="secondary remove">&times;</button></td>';
+            row.querySelector('.kind').value = kind;
+            row.querySelector('.remove').addEventListener('click', function() {{ row.remove(); updateTotals(); }});
+            row.querySelector('.description').value = description;
+            row.addEventListener('input', updateTotals);
+            items.appendChild(row);
+            updateTotals();
+        }}
+        function collect() {{
+            return Array.from(items.querySelectorAll('tr')).map(function(row) {{
+                return {{
+                    kind: row.querySelector('.kind').value,
+                    description: row.querySelector('.description').value,
+                    quantity: parseFloat(row.querySelector('.quantity').value) || 0,
+                    unit_price: parseFloat(row.querySelector('.unit-price').value) || 0
+                }};
+            }});
+        }}
+        function updateTotals() {{
+            const subtotal = collect().reduce(function(sum, item) {{ return sum + Math.round(item.quantity * item.unit_price * 100); }}, 0);
+            const tax = Math.round(subtotal * (parseFloat(document.getElementById('tax-rate').value) || 0) / 100);
+            document.getElementById('totals').textContent = 'Total: $' + ((subtotal + tax) / 100).toFixed(2) + ' ' + document.getElementById('currency').value;
+        }}
+        document.getElementById('add-item').addEventListener('click', function() {{ addItem('parts', ''); }});
+        document.getElementById('tax-rate').addEventListener('input', updateTotals);
+        document.getElementById('currency').addEventListener('change', updateTotals);
+        document.getElementById('quote-form').addEventListener('submit', async function(e) {{
+            e.preventDefault();
+            const response = await fetch('/api/quotes', {{
+                method: 'POST',
+                headers: {{ 'Content-Type': 'application/json' }},
+                body: JSON.stringify({{
+                    inquiry_id: {inquiry_json},
+                    currency: document.getElementById('currency').value,
+                    tax_rate: parseFloat(document.getElementById('tax-rate').value),
+                    valid_days: parseInt(document.getElementById('valid-days').value, 10),
+                    items: collect(),
+                    notes: document.getElementById('notes').value
+                }})
+            }});
+            const result = await response.json();
+            if (result.success) {{ location.href = '/contact-admin/quotes'; }}
+            else {{ document.getElementById('message').textContent = result.message; }}
+        }});
+        addItem('labor', {service_json});
+    </script>
+</body>
+</html>"#,
+        lang = lang.code(),
+        style = ADMIN_STYLE,
+        name = html_escape(&inquiry.name),
+        email = html_escape(&inquiry.email),
+        phone = html_escape(if inquiry.phone.is_empty() { t("no phone", "sin teléfono") } else { &inquiry.phone }),
+        service = html_escape(&service),
+        id = html_escape(&inquiry.id),
+        tax = DEFAULT_TAX_RATE,
+        days = DEFAULT_VALID_DAYS,
+        nonce = security_headers::nonce(&req),
+        inquiry_json = js_string(&inquiry.id),
+        service_json = js_string(&service),
+        kinds_json = js_string(&kinds),
+        t_title = t("New Quote", "Nueva cotización"),
+        t_back = t("Back to Admin", "Volver al panel"),
+        t_inquiry = t("inquiry", "solicitud"),
+        t_currency = t("Currency", "Moneda"),
+        t_valid_days = t("Valid for (days)", "Vigencia (días)"),
+        t_type = t("Type", "Tipo"),
+        t_description = t("Description", "Descripción"),
+        t_qty = t("Qty", "Cant."),
+        t_unit_price = t("Unit Price", "Precio unitario"),
+        t_add_item = t("Add item", "Agregar concepto"),
+        t_notes = t("Notes", "Notas"),
+        t_create = t("Create quote", "Crear cotización"),
+    ))
+}
+
+pub fn configure_billing(cfg: &mut web::ServiceConfig) {
+    cfg.route("/contact-admin/quotes", web::get().to(quotes_admin))
+        .route("/contact-admin/quotes/new/{inquiry_id}", web::get().to(new_quote_form))
+        .route("/contact-admin/quotes/{id}/pdf", web::get().to(quote_pdf))
+        .route("/contact-admin/invoices/{number}/pdf", web::get().to(invoice_pdf))
+        .route("/api/quotes", web::post().to(create_quote))
+        .route("/api/quotes/{id}/invoice", web::post().to(convert_to_invoice));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: ItemKind, quantity: f64, unit_price: f64) -> LineItem {
+        LineItem { kind, description: "Item".to_string(), quantity, unit_price }
+    }
+
+    fn inquiry() -> crate::ServiceInquiryRecord {
+        crate::ServiceInquiryRecord {
+            id: "inq00001".to_string(),
+            timestamp: "2026-01-01 10:00:00".to_string(),
+            service_type: "computer-repair".to_string(),
+            name: "Ana".to_string(),
+            email: "ana@example.com".to_string(),
+            phone: String::new(),
+            details: String::new(),
+            answers: serde_json::json!({}),
+        }
+    }
+
+    fn request(items: Vec<LineItem>) -> QuoteRequest {
+        QuoteRequest {
+            inquiry_id: "inq00001".to_string(),
+            currency: Currency::Mxn,
+            tax_rate: None,
+            valid_days: None,
+            items,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_format_money() {
+        assert_eq!(format_money(0, Currency::Mxn), "$0.00 MXN");
+        assert_eq!(format_money(123456789, Currency::Usd), "$1,234,567.89 USD");
+        assert_eq!(format_money(-505, Currency::Mxn), "-$5.05 MXN");
+    }
+
+    #[test]
+    fn test_totals_with_iva() {
+        let mut quote = build_quote(
+            &request(vec![item(ItemKind::Labor, 1.5, 450.0), item(ItemKind::Parts, 2.0, 899.99)]),
+            &inquiry(),
+        )
+        .unwrap();
+        let totals = quote.totals();
+        assert_eq!(totals.subtotal, 67500 + 179998);
+        assert_eq!(totals.tax, 39600);
+        assert_eq!(totals.total, 247498 + 39600);
+
+        quote.tax_rate = 0.0;
+        assert_eq!(quote.totals().tax, 0);
+    }
+
+    #[test]
+    fn test_build_quote_validates() {
+        assert!(build_quote(&request(vec![]), &inquiry()).is_err());
+        assert!(build_quote(&request(vec![item(ItemKind::Labor, 0.0, 100.0)]), &inquiry()).is_err());
+        assert!(build_quote(&request(vec![item(ItemKind::Labor, 1.0, -1.0)]), &inquiry()).is_err());
+        let mut bad_tax = request(vec![item(ItemKind::Labor, 1.0, 100.0)]);
+        bad_tax.tax_rate = Some(160.0);
+        assert!(build_quote(&bad_tax, &inquiry()).is_err());
+
+        let quote = build_quote(&request(vec![item(ItemKind::Labor, 1.0, 100.0)]), &inquiry()).unwrap();
+        assert_eq!(quote.inquiry_id, "inq00001");
+        assert_eq!(quote.tax_rate, DEFAULT_TAX_RATE);
+        assert_eq!(quote.valid_until.len(), 10);
+    }
+
+    #[test]
+    fn test_request_json() {
+        let request: QuoteRequest = serde_json::from_str(
+            r#"{"inquiry_id":"x","currency":"USD","items":[{"kind":"parts","description":"SSD","quantity":1,"unit_price":79.5}]}"#,
+        )
+        .unwrap();
+        assert_eq!(request.currency, Currency::Usd);
+        assert_eq!(request.items[0].kind, ItemKind::Parts);
+        assert_eq!(request.items[0].total_cents(), 7950);
+    }
+
+    #[test]
+    fn test_invoice_label_and_pdfs() {
+        let quote = build_quote(&request(vec![item(ItemKind::Labor, 2.0, 350.0)]), &inquiry()).unwrap();
+        let invoice = Invoice {
+            number: 42,
+            issued: "2026-01-02".to_string(),
+            due: "2026-01-17".to_string(),
+            quote_id: quote.id.clone(),
+        };
+        assert_eq!(invoice.label(), "SCC-00042");
+        assert!(generate_billing_pdf(&quote, None).unwrap().starts_with(b"%PDF"));
+        assert!(generate_billing_pdf(&quote, Some(&invoice)).unwrap().starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,926 @@
+// Server configuration
+// scc-server, scc-dev and scc-desktop read the same settings, each layer
+// overriding the one before it:
+//   1. built-in defaults
+//   2. a TOML file: --config PATH, else $SCC_CONFIG, else ./scc.toml if present
+//   3. environment variables (ENV_HELP lists them)
+//   4. command-line flags (FLAG_HELP)
+// `--print-config` prints the merged result as TOML and exits, which also
+// makes a starting point for a config file.
+//
+// Data files (the CSV stores, accounts.txt) live under storage.data_dir, so a
+// container only needs one volume for them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DEFAULT_CONFIG_FILE: &str = "scc.toml";
+
+/// Rules for cross-origin requests: "path METHODS headers" (see cors.rs)
+const DEFAULT_CORS_ROUTES: &[&str] = &[
+    "/api/contact POST content-type",
+    "/api/service-inquiry POST content-type",
+    "/api/honeypot POST content-type",
+    "/health GET",
+    "/whitepaper/pdf/* GET",
+    "/* GET",
+];
+
+/// The built-in decoys (see decoys.rs)
+const DEFAULT_DECOYS: &[&str] = &["/wp-login.php", "/xmlrpc.php", "/.env", "/.git/config", "/phpmyadmin/index.php", "/server-status"];
+
+/// Pages and images; scripts and styles always come from the build
+const DEFAULT_OVERLAY_ALLOW: &[&str] = &["*.html", "images/*.webp", "images/*.png", "images/backgrounds/*.webp"];
+
+const LOG_LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+/// What log lines can have redacted (see logging.rs)
+const REDACTIONS: &[&str] = &["email", "phone", "password", "ip"];
+
+pub const FLAG_HELP: &str = "\
+Options:
+  --config <file>             TOML config file (default: $SCC_CONFIG, or ./scc.toml if present)
+  --bind <addr>               Address to listen on; repeat for several (default: 0.0.0.0:9000)
+  --port <port>               Port for every bind address
+  --data-dir <dir>            Directory holding the CSV stores and accounts.txt
+  --static-dir <dir>          Site files served by scc-dev
+  --public-url <url>          Base URL printed in documents and sitemap links
+  --storage-backend <name>    Record storage (csv)
+  --accounts-file <file>      Admin accounts, relative to the data directory
+  --cors-origins <list>       Comma-separated origins allowed cross-origin requests
+  --rate-limit-forms <n>      Form submissions per client IP per minute; 0 for no limit
+  --log-level <level>         off, error, warn, info, debug or trace
+  --log-format <fmt>          text or json
+  --log-modules <list>        Per-module levels, e.g. actix_server=warn,scc_server::decoys=debug
+  --print-config              Print the effective configuration as TOML and exit
+  -h, --help                  Show this help
+
+Other settings (TLS, security headers, metrics, honeypot, analytics, ...) are
+set in the config file or the environment.";
+
+/// Flags that take a value
+const VALUE_FLAGS: &[&str] = &[
+    "--config",
+    "--bind",
+    "--port",
+    "--data-dir",
+    "--static-dir",
+    "--public-url",
+    "--storage-backend",
+    "--accounts-file",
+    "--cors-origins",
+    "--rate-limit-forms",
+    "--log-level",
+    "--log-format",
+    "--log-modules",
+];
+
+pub const ENV_HELP: &str = "\
+Environment (overrides the config file, overridden by flags):
+  BIND, PORT, PUBLIC_URL, STATIC_DIR, SHUTDOWN_TIMEOUT, TLS_BIND, TLS_CERT, TLS_KEY,
+  TLS_REDIRECT, TLS_WATCH_SECS, DATA_DIR, STORAGE_BACKEND, ACCOUNTS_FILE,
+  CORS_ORIGINS, CORS_ROUTES, CORS_MAX_AGE, RATE_LIMIT_FORMS, RATE_LIMIT_PROXIES, LOG_LEVEL,
+  LOG_MODULES, LOG_FORMAT, LOG_REDACT, ACCESS_LOG, METRICS_TOKEN, METRICS_ALLOW,
+  HEALTH_MIN_FREE_MB, HEALTH_MAX_IN_FLIGHT,
+  SECURITY_HEADERS, HSTS_MAX_AGE, CSP_SITE, CSP_ADMIN, CSP_VIEW, CSP_API, CSP_DECOY,
+  HONEYPOT_DECOYS, HONEYPOT_TARPIT_MS, ROBOTS_DISALLOW, ROBOTS_INDEX, ANALYTICS,
+  ANALYTICS_FLUSH_SECS, OVERLAY_DIR, OVERLAY_ALLOW, LIVE_RELOAD";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub tls: TlsConfig,
+    pub storage: StorageConfig,
+    pub auth: AuthConfig,
+    pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub log: LogConfig,
+    pub metrics: MetricsConfig,
+    pub health: HealthConfig,
+    pub security_headers: SecurityHeadersConfig,
+    pub honeypot: HoneypotConfig,
+    pub robots: RobotsConfig,
+    pub analytics: AnalyticsConfig,
+    pub overlay: OverlayConfig,
+    pub dev: DevConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Addresses to listen on ("host:port"); port 0 picks a free one
+    pub bind: Vec<String>,
+    /// Base URL the site is published at, without a trailing slash
+    pub public_url: String,
+    /// Site files scc-dev serves from disk
+    pub static_dir: PathBuf,
+    /// Seconds scc-server waits for in-flight requests when shutting down
+    pub shutdown_timeout: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind: vec!["0.0.0.0:9000".to_string()],
+            public_url: "https://southcitycomputer.com".to_string(),
+            static_dir: PathBuf::from(".."),
+            shutdown_timeout: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Addresses scc-server serves HTTPS on, when a certificate is set
+    pub bind: Vec<String>,
+    /// PEM certificate chain; unset to leave HTTPS to a proxy
+    pub cert: Option<PathBuf>,
+    /// PEM private key for the certificate
+    pub key: Option<PathBuf>,
+    /// Redirect plain HTTP (server.bind) to HTTPS
+    pub redirect: bool,
+    /// Seconds between checks of the certificate files for changes; 0 to
+    /// reload on SIGHUP only
+    pub watch_secs: u64,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig { bind: vec!["0.0.0.0:443".to_string()], cert: None, key: None, redirect: true, watch_secs: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// One CSV file per record type (see records.rs)
+    #[default]
+    Csv,
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(StorageBackend::Csv),
+            other => Err(format!("unknown storage backend {:?} (expected \"csv\")", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    pub data_dir: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig { backend: StorageBackend::Csv, data_dir: PathBuf::from(".") }
+    }
+}
+
+impl StorageConfig {
+    /// `file` inside the data directory (absolute paths are kept as they are)
+    pub fn path(&self, file: impl AsRef<Path>) -> PathBuf {
+        self.data_dir.join(file)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// "user:password" lines for the admin pages, relative to the data directory
+    pub accounts_file: PathBuf,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig { accounts_file: PathBuf::from("accounts.txt") }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins allowed cross-origin requests; "*" for any
+    pub origins: Vec<String>,
+    /// "path METHODS headers" rules, first match wins
+    pub routes: Vec<String>,
+    /// Seconds browsers may cache a preflight
+    pub max_age: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig { origins: Vec::new(), routes: DEFAULT_CORS_ROUTES.iter().map(|r| r.to_string()).collect(), max_age: 3600 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Contact and service inquiry submissions allowed per client IP per
+    /// minute; 0 for no limit
+    pub form_posts_per_minute: u32,
+    /// Proxies ("127.0.0.1", "10.0.0.0/8") whose X-Forwarded-For is believed
+    /// when working out the client IP; other peers are counted by their own
+    /// address
+    pub trusted_proxies: Vec<String>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig { form_posts_per_minute: 10, trusted_proxies: vec!["127.0.0.1".to_string(), "::1".to_string()] }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// "2026-01-05 14:03:22 INFO  target: [request id] message"
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("unknown log format {:?} (expected \"text\" or \"json\")", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub level: String,
+    /// Levels for particular modules ("scc_server::decoys", "actix_server"),
+    /// overriding `level` for them and everything under them
+    pub modules: BTreeMap<String, String>,
+    pub format: LogFormat,
+    /// What to mask in log lines: email, phone, password, ip
+    pub redact: Vec<String>,
+    /// One line per request
+    pub access_log: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+            format: LogFormat::Text,
+            redact: REDACTIONS.iter().map(|r| r.to_string()).collect(),
+            access_log: true,
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn level_filter(&self) -> log::LevelFilter {
+        self.level.parse().unwrap_or(log::LevelFilter::Info)
+    }
+
+    /// (module, level) for every module with its own level
+    pub fn module_filters(&self) -> Vec<(String, log::LevelFilter)> {
+        self.modules.iter().map(|(module, level)| (module.clone(), level.parse().unwrap_or(log::LevelFilter::Info))).collect()
+    }
+
+    pub fn redacts(&self, what: &str) -> bool {
+        self.redact.iter().any(|r| r == what)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Bearer token that may scrape /metrics from anywhere; unset for none
+    pub token: Option<String>,
+    /// Client addresses ("10.0.0.5", "10.0.0.0/8") that may scrape without
+    /// the token
+    pub allow: Vec<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig { token: None, allow: vec!["127.0.0.1".to_string(), "::1".to_string()] }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// /health/ready fails with less free space than this in data_dir (MiB)
+    pub min_free_mb: u64,
+    /// /health/ready fails with more requests than this in flight; 0 for no
+    /// limit
+    pub max_in_flight: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig { min_free_mb: 100, max_in_flight: 1000 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityHeadersConfig {
+    /// Route groups that get the headers: site, admin, view, api, decoy
+    pub groups: Vec<String>,
+    /// Strict-Transport-Security max-age; 0 drops the header
+    pub hsts_max_age: u64,
+    /// Content-Security-Policy per group, replacing the built-in one; "{nonce}"
+    /// is filled in per response, "off" drops the header
+    pub csp: BTreeMap<String, String>,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        SecurityHeadersConfig {
+            groups: ["site", "admin", "view", "api"].iter().map(|g| g.to_string()).collect(),
+            hsts_max_age: 31_536_000,
+            csp: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HoneypotConfig {
+    /// Decoy paths, each a built-in one or "path=kind"
+    pub decoys: Vec<String>,
+    /// Drip decoy bodies out with this delay between chunks; 0 to send at once
+    pub tarpit_ms: u64,
+}
+
+impl Default for HoneypotConfig {
+    fn default() -> Self {
+        HoneypotConfig { decoys: DEFAULT_DECOYS.iter().map(|d| d.to_string()).collect(), tarpit_ms: 0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RobotsConfig {
+    /// Path prefixes to disallow (and leave out of the sitemap) on top of the
+    /// admin, record, API and honeypot paths
+    pub disallow: Vec<String>,
+    /// false disallows everything, e.g. on a staging copy
+    pub index: bool,
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        RobotsConfig { disallow: Vec::new(), index: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyticsConfig {
+    /// Count page views (no cookies, no stored addresses)
+    pub enabled: bool,
+    /// Seconds between writes of the counts to page_views.csv
+    pub flush_secs: u64,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        AnalyticsConfig { enabled: true, flush_secs: 300 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OverlayConfig {
+    /// scc-server: files here replace the embedded ones; unset for none
+    pub dir: Option<PathBuf>,
+    /// Glob patterns a file must match to be served from the overlay
+    pub allow: Vec<String>,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        OverlayConfig { dir: None, allow: DEFAULT_OVERLAY_ALLOW.iter().map(|p| p.to_string()).collect() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DevConfig {
+    /// scc-dev: reload pages (or swap stylesheets) when a file in static_dir
+    /// changes
+    pub live_reload: bool,
+    /// How often scc-dev checks static_dir for changes
+    pub watch_interval_ms: u64,
+}
+
+impl Default for DevConfig {
+    fn default() -> Self {
+        DevConfig { live_reload: true, watch_interval_ms: 300 }
+    }
+}
+
+/// Command line of a server binary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cli {
+    pub config_file: Option<PathBuf>,
+    /// (flag, value) in the order given
+    pub overrides: Vec<(String, String)>,
+    pub print_config: bool,
+    pub help: bool,
+    /// Positional arguments, e.g. "migrate"
+    pub args: Vec<String>,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let mut cli = Cli::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "-h" | "--help" => cli.help = true,
+            "--print-config" => cli.print_config = true,
+            _ if VALUE_FLAGS.contains(&flag) => {
+                let value = match inline {
+                    Some(value) => value,
+                    None => iter.next().cloned().ok_or_else(|| format!("{} needs a value", flag))?,
+                };
+                if flag == "--config" {
+                    cli.config_file = Some(PathBuf::from(value));
+                } else {
+                    cli.overrides.push((flag.to_string(), value));
+                }
+            }
+            _ if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+            _ => cli.args.push(arg.clone()),
+        }
+    }
+    Ok(cli)
+}
+
+fn list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect()
+}
+
+/// A list where "off" or "none" means empty
+fn list_or_off(value: &str) -> Vec<String> {
+    if value.trim().eq_ignore_ascii_case("off") || value.trim().eq_ignore_ascii_case("none") {
+        Vec::new()
+    } else {
+        list(value)
+    }
+}
+
+/// "module=level" pairs, comma-separated
+fn log_modules(name: &str, value: &str) -> Result<BTreeMap<String, String>, String> {
+    list(value)
+        .iter()
+        .map(|entry| match entry.split_once('=') {
+            Some((module, level)) => Ok((module.trim().to_string(), level.trim().to_ascii_lowercase())),
+            None => Err(format!("{}: {:?} is not module=level", name, entry)),
+        })
+        .collect()
+}
+
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.trim().parse().map_err(|_| format!("{}: {:?} is not a valid number", name, value))
+}
+
+fn boolean(name: &str, value: &str) -> Result<bool, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("{}: {:?} is not on or off", name, value)),
+    }
+}
+
+impl Config {
+    /// The full stack: defaults (listening on `default_bind`), config file,
+    /// environment, flags
+    pub fn load(cli: &Cli, default_bind: &str, env: impl Fn(&str) -> Option<String>) -> Result<Config, String> {
+        let explicit = cli.config_file.clone().or_else(|| env("SCC_CONFIG").map(PathBuf::from));
+        let file = explicit.clone().or_else(|| Path::new(DEFAULT_CONFIG_FILE).exists().then(|| PathBuf::from(DEFAULT_CONFIG_FILE)));
+
+        let text = match &file {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?),
+            None => None,
+        };
+        let mut config = Config::from_file(text.as_deref().unwrap_or(""), default_bind).map_err(|e| match &file {
+            Some(path) => format!("{}: {}", path.display(), e),
+            None => e,
+        })?;
+        config.apply_env(env)?;
+        config.apply_flags(&cli.overrides)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// A config file's settings, listening on `default_bind` unless the file
+    /// sets server.bind itself (even to the same address)
+    fn from_file(text: &str, default_bind: &str) -> Result<Config, String> {
+        let mut config = Config::from_toml(text)?;
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        if table.get("server").and_then(|server| server.get("bind")).is_none() {
+            config.server.bind = vec![default_bind.to_string()];
+        }
+        Ok(config)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Config, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+
+    fn set_port(&mut self, port: &str) -> Result<(), String> {
+        let port: u16 = number("port", port)?;
+        for addr in &mut self.server.bind {
+            let host = addr.rsplit_once(':').map(|(host, _)| host).unwrap_or(addr.as_str());
+            *addr = format!("{}:{}", host, port);
+        }
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(v) = env("BIND") {
+            self.server.bind = list(&v);
+        }
+        if let Some(v) = env("PORT") {
+            self.set_port(&v)?;
+        }
+        if let Some(v) = env("PUBLIC_URL") {
+            self.server.public_url = v;
+        }
+        if let Some(v) = env("STATIC_DIR") {
+            self.server.static_dir = PathBuf::from(v);
+        }
+        if let Some(v) = env("SHUTDOWN_TIMEOUT") {
+            self.server.shutdown_timeout = number("SHUTDOWN_TIMEOUT", &v)?;
+        }
+        if let Some(v) = env("TLS_BIND") {
+            self.tls.bind = list(&v);
+        }
+        if let Some(v) = env("TLS_CERT") {
+            self.tls.cert = Some(PathBuf::from(v)).filter(|path| !path.as_os_str().is_empty());
+        }
+        if let Some(v) = env("TLS_KEY") {
+            self.tls.key = Some(PathBuf::from(v)).filter(|path| !path.as_os_str().is_empty());
+        }
+        if let Some(v) = env("TLS_REDIRECT") {
+            self.tls.redirect = boolean("TLS_REDIRECT", &v)?;
+        }
+        if let Some(v) = env("TLS_WATCH_SECS") {
+            self.tls.watch_secs = number("TLS_WATCH_SECS", &v)?;
+        }
+        if let Some(v) = env("DATA_DIR") {
+            self.storage.data_dir = PathBuf::from(v);
+        }
+        if let Some(v) = env("STORAGE_BACKEND") {
+            self.storage.backend = v.parse()?;
+        }
+        if let Some(v) = env("ACCOUNTS_FILE") {
+            self.auth.accounts_file = PathBuf::from(v);
+        }
+        if let Some(v) = env("CORS_ORIGINS") {
+            self.cors.origins = list(&v);
+        }
+        if let Some(v) = env("CORS_ROUTES") {
+            self.cors.routes = v.split(';').map(str::trim).filter(|r| !r.is_empty()).map(str::to_string).collect();
+        }
+        if let Some(v) = env("CORS_MAX_AGE") {
+            self.cors.max_age = number("CORS_MAX_AGE", &v)?;
+        }
+        if let Some(v) = env("RATE_LIMIT_FORMS") {
+            self.rate_limit.form_posts_per_minute = number("RATE_LIMIT_FORMS", &v)?;
+        }
+        if let Some(v) = env("RATE_LIMIT_PROXIES") {
+            self.rate_limit.trusted_proxies = list_or_off(&v);
+        }
+        if let Some(v) = env("LOG_LEVEL") {
+            self.log.level = v.trim().to_ascii_lowercase();
+        }
+        if let Some(v) = env("LOG_MODULES") {
+            self.log.modules = log_modules("LOG_MODULES", &v)?;
+        }
+        if let Some(v) = env("LOG_FORMAT") {
+            self.log.format = v.parse()?;
+        }
+        if let Some(v) = env("LOG_REDACT") {
+            self.log.redact = list_or_off(&v).into_iter().map(|r| r.to_ascii_lowercase()).collect();
+        }
+        if let Some(v) = env("ACCESS_LOG") {
+            self.log.access_log = boolean("ACCESS_LOG", &v)?;
+        }
+        if let Some(v) = env("METRICS_TOKEN") {
+            self.metrics.token = Some(v.trim().to_string()).filter(|token| !token.is_empty());
+        }
+        if let Some(v) = env("METRICS_ALLOW") {
+            self.metrics.allow = list_or_off(&v);
+        }
+        if let Some(v) = env("HEALTH_MIN_FREE_MB") {
+            self.health.min_free_mb = number("HEALTH_MIN_FREE_MB", &v)?;
+        }
+        if let Some(v) = env("HEALTH_MAX_IN_FLIGHT") {
+            self.health.max_in_flight = number("HEALTH_MAX_IN_FLIGHT", &v)?;
+        }
+        if let Some(v) = env("SECURITY_HEADERS") {
+            self.security_headers.groups = list_or_off(&v);
+        }
+        if let Some(v) = env("HSTS_MAX_AGE") {
+            self.security_headers.hsts_max_age = number("HSTS_MAX_AGE", &v)?;
+        }
+        for group in crate::security_headers::GROUPS.map(|group| group.name()) {
+            if let Some(v) = env(&format!("CSP_{}", group.to_uppercase())) {
+                self.security_headers.csp.insert(group.to_string(), v.trim().to_string());
+            }
+        }
+        if let Some(v) = env("HONEYPOT_DECOYS") {
+            self.honeypot.decoys = list_or_off(&v);
+        }
+        if let Some(v) = env("HONEYPOT_TARPIT_MS") {
+            self.honeypot.tarpit_ms = number("HONEYPOT_TARPIT_MS", &v)?;
+        }
+        if let Some(v) = env("ROBOTS_DISALLOW") {
+            self.robots.disallow = list(&v);
+        }
+        if let Some(v) = env("ROBOTS_INDEX") {
+            self.robots.index = boolean("ROBOTS_INDEX", &v)?;
+        }
+        if let Some(v) = env("ANALYTICS") {
+            self.analytics.enabled = boolean("ANALYTICS", &v)?;
+        }
+        if let Some(v) = env("ANALYTICS_FLUSH_SECS") {
+            self.analytics.flush_secs = number("ANALYTICS_FLUSH_SECS", &v)?;
+        }
+        if let Some(v) = env("OVERLAY_DIR") {
+            self.overlay.dir = Some(PathBuf::from(v)).filter(|dir| !dir.as_os_str().is_empty());
+        }
+        if let Some(v) = env("OVERLAY_ALLOW") {
+            self.overlay.allow = list(&v);
+        }
+        if let Some(v) = env("LIVE_RELOAD") {
+            self.dev.live_reload = boolean("LIVE_RELOAD", &v)?;
+        }
+        Ok(())
+    }
+
+    fn apply_flags(&mut self, overrides: &[(String, String)]) -> Result<(), String> {
+        let mut binds = Vec::new();
+        for (flag, value) in overrides {
+            match flag.as_str() {
+                "--bind" => binds.push(value.clone()),
+                "--port" => {}
+                "--data-dir" => self.storage.data_dir = PathBuf::from(value),
+                "--static-dir" => self.server.static_dir = PathBuf::from(value),
+                "--public-url" => self.server.public_url = value.clone(),
+                "--storage-backend" => self.storage.backend = value.parse()?,
+                "--accounts-file" => self.auth.accounts_file = PathBuf::from(value),
+                "--cors-origins" => self.cors.origins = list(value),
+                "--rate-limit-forms" => self.rate_limit.form_posts_per_minute = number("--rate-limit-forms", value)?,
+                "--log-level" => self.log.level = value.trim().to_ascii_lowercase(),
+                "--log-format" => self.log.format = value.parse()?,
+                "--log-modules" => self.log.modules = log_modules("--log-modules", value)?,
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+        if !binds.is_empty() {
+            self.server.bind = binds;
+        }
+        // After --bind, so "--bind 127.0.0.1:0 --port 8080" means what it says
+        if let Some((_, port)) = overrides.iter().rev().find(|(flag, _)| flag == "--port") {
+            self.set_port(port)?;
+        }
+        Ok(())
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        if self.server.bind.is_empty() {
+            return Err("server.bind: at least one address is needed".to_string());
+        }
+        if let Some(addr) = self.server.bind.iter().find(|addr| !addr.contains(':')) {
+            return Err(format!("server.bind: {:?} has no port", addr));
+        }
+        if self.tls.cert.is_some() != self.tls.key.is_some() {
+            return Err("tls.cert and tls.key: set both or neither".to_string());
+        }
+        if let Some(addr) = self.tls.bind.iter().find(|addr| !addr.contains(':')) {
+            return Err(format!("tls.bind: {:?} has no port", addr));
+        }
+        self.server.public_url = self.server.public_url.trim().trim_end_matches('/').to_string();
+        if !self.server.public_url.starts_with("http://") && !self.server.public_url.starts_with("https://") {
+            return Err(format!("server.public_url: {:?} is not an http(s) URL", self.server.public_url));
+        }
+        if let Some(pattern) = self.overlay.allow.iter().find(|pattern| globset::Glob::new(pattern).is_err()) {
+            return Err(format!("overlay.allow: {:?} is not a valid pattern", pattern));
+        }
+        if let Some(entry) = self.metrics.allow.iter().find(|entry| crate::metrics::parse_network(entry).is_none()) {
+            return Err(format!("metrics.allow: {:?} is not an address or network", entry));
+        }
+        if let Some(entry) = self.rate_limit.trusted_proxies.iter().find(|entry| crate::metrics::parse_network(entry).is_none()) {
+            return Err(format!("rate_limit.trusted_proxies: {:?} is not an address or network", entry));
+        }
+        if self.analytics.flush_secs == 0 {
+            return Err("analytics.flush_secs: must be above zero".to_string());
+        }
+        if self.dev.watch_interval_ms == 0 {
+            return Err("dev.watch_interval_ms: must be above zero".to_string());
+        }
+        if !LOG_LEVELS.contains(&self.log.level.as_str()) {
+            return Err(format!("log.level: {:?} is not one of {}", self.log.level, LOG_LEVELS.join(", ")));
+        }
+        if let Some((module, level)) = self.log.modules.iter().find(|(_, level)| !LOG_LEVELS.contains(&level.as_str())) {
+            return Err(format!("log.modules: {:?} for {} is not one of {}", level, module, LOG_LEVELS.join(", ")));
+        }
+        if let Some(what) = self.log.redact.iter().find(|what| !REDACTIONS.contains(&what.as_str())) {
+            return Err(format!("log.redact: {:?} is not one of {}", what, REDACTIONS.join(", ")));
+        }
+        Ok(())
+    }
+
+    /// Where the admin accounts are read from
+    pub fn accounts_path(&self) -> PathBuf {
+        self.storage.path(&self.auth.accounts_file)
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The command line and default address the running configuration came from
+static LOADED_FROM: OnceLock<(Cli, String)> = OnceLock::new();
+
+/// The effective configuration; the defaults until a binary has loaded its own
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Load the configuration for a binary from its command line and the
+/// environment. Handles --help and --print-config (and errors) by exiting;
+/// otherwise makes the result what get() returns and hands back the command
+/// line for any positional arguments.
+pub fn init(binary: &str, default_bind: &str, extra_help: &str) -> Cli {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}: {}\n\n{}", binary, e, FLAG_HELP);
+        std::process::exit(2);
+    });
+    if cli.help {
+        println!("Usage: {} [options]{}\n\n{}\n\n{}", binary, extra_help, FLAG_HELP, ENV_HELP);
+        std::process::exit(0);
+    }
+    let config = Config::load(&cli, default_bind, |name| std::env::var(name).ok()).unwrap_or_else(|e| {
+        eprintln!("{}: invalid configuration: {}", binary, e);
+        std::process::exit(2);
+    });
+    if cli.print_config {
+        print!("{}", config.to_toml());
+        std::process::exit(0);
+    }
+    let _ = CONFIG.set(config);
+    let _ = LOADED_FROM.set((cli.clone(), default_bind.to_string()));
+    cli
+}
+
+/// Load the configuration again the way init() did: an error means the
+/// config file or environment has changed so that a restart would fail
+pub fn check_reload() -> Result<(), String> {
+    match LOADED_FROM.get() {
+        Some((cli, default_bind)) => Config::load(cli, default_bind, |name| std::env::var(name).ok()).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn load(line: &str, env: &[(&str, &str)]) -> Result<Config, String> {
+        let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Config::load(&parse_args(&args(line))?, "0.0.0.0:9000", |name| env.get(name).cloned())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cli = parse_args(&args("migrate --bind 127.0.0.1:80 --bind=[::1]:80 --print-config --config x.toml")).unwrap();
+        assert_eq!(cli.args, vec!["migrate".to_string()]);
+        assert_eq!(cli.overrides.len(), 2);
+        assert_eq!(cli.overrides[1], ("--bind".to_string(), "[::1]:80".to_string()));
+        assert!(cli.print_config);
+        assert_eq!(cli.config_file, Some(PathBuf::from("x.toml")));
+        assert!(parse_args(&args("--bogus")).is_err());
+        assert!(parse_args(&args("--port")).is_err());
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let config = load("--port 8081", &[("PORT", "8080"), ("DATA_DIR", "/var/lib/scc"), ("PUBLIC_URL", "http://shop.test/")]).unwrap();
+        assert_eq!(config.server.bind, vec!["0.0.0.0:8081".to_string()]);
+        assert_eq!(config.server.public_url, "http://shop.test");
+        assert_eq!(config.accounts_path(), PathBuf::from("/var/lib/scc/accounts.txt"));
+
+        let config = load("--bind 127.0.0.1:0 --bind [::]:0 --port 9001", &[]).unwrap();
+        assert_eq!(config.server.bind, vec!["127.0.0.1:9001".to_string(), "[::]:9001".to_string()]);
+
+        let config = load(
+            "--rate-limit-forms 3 --cors-origins https://a.test,https://b.test --log-format json --log-modules actix_server=warn",
+            &[("RATE_LIMIT_FORMS", "5"), ("LOG_FORMAT", "text")],
+        )
+        .unwrap();
+        assert_eq!(config.rate_limit.form_posts_per_minute, 3);
+        assert_eq!(config.cors.origins.len(), 2);
+        assert_eq!(config.log.format, LogFormat::Json);
+        assert_eq!(config.log.modules.get("actix_server").map(String::as_str), Some("warn"));
+    }
+
+    #[test]
+    fn test_env_lists() {
+        let config = load(
+            "",
+            &[
+                ("HONEYPOT_DECOYS", "off"),
+                ("SECURITY_HEADERS", "admin, view"),
+                ("CORS_ROUTES", "/api/contact POST content-type; /* GET"),
+                ("CSP_VIEW", "default-src 'self'"),
+                ("CSP_DECOY", "default-src 'none'"),
+                ("ROBOTS_INDEX", "off"),
+                ("LOG_MODULES", "scc_server::decoys=debug, actix_server=warn"),
+                ("LOG_REDACT", "email,ip"),
+                ("ANALYTICS", "off"),
+                ("TLS_BIND", "0.0.0.0:8443, [::]:8443"),
+            ],
+        )
+        .unwrap();
+        assert!(config.honeypot.decoys.is_empty());
+        assert_eq!(config.security_headers.groups, vec!["admin".to_string(), "view".to_string()]);
+        assert_eq!(config.cors.routes.len(), 2);
+        assert_eq!(config.security_headers.csp.get("view").map(String::as_str), Some("default-src 'self'"));
+        assert_eq!(config.security_headers.csp.get("decoy").map(String::as_str), Some("default-src 'none'"));
+        assert!(!config.robots.index);
+        assert_eq!(config.log.modules.get("actix_server").map(String::as_str), Some("warn"));
+        assert!(config.log.redacts("ip") && !config.log.redacts("password"));
+        assert!(!config.analytics.enabled);
+        assert_eq!(config.tls.bind, vec!["0.0.0.0:8443".to_string(), "[::]:8443".to_string()]);
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        assert!(load("", &[("PORT", "ninety")]).is_err());
+        assert!(load("--log-level loud", &[]).is_err());
+        assert!(load("", &[("STORAGE_BACKEND", "postgres")]).is_err());
+        assert!(load("--public-url shop.test", &[]).is_err());
+        assert!(Config::from_toml("[server]\nport = 80\n").is_err());
+        assert!(load("", &[("OVERLAY_ALLOW", "images/[*.webp")]).is_err());
+        assert!(load("", &[("METRICS_ALLOW", "10.0.0.0/33")]).is_err());
+        assert!(load("", &[("RATE_LIMIT_PROXIES", "nginx")]).is_err());
+        assert!(load("", &[("LOG_MODULES", "scc_server::decoys")]).is_err());
+        assert!(load("", &[("LOG_MODULES", "scc_server::decoys=loud")]).is_err());
+        assert!(load("", &[("LOG_REDACT", "names")]).is_err());
+        assert!(load("", &[("LOG_FORMAT", "xml")]).is_err());
+        assert!(load("--storage-backend postgres", &[]).is_err());
+        assert!(load("--rate-limit-forms many", &[]).is_err());
+        assert!(load("", &[("ANALYTICS_FLUSH_SECS", "0")]).is_err());
+        assert!(load("", &[("SHUTDOWN_TIMEOUT", "soon")]).is_err());
+        assert!(load("", &[("TLS_CERT", "/etc/scc/cert.pem")]).is_err());
+        assert!(load("", &[("TLS_CERT", "cert.pem"), ("TLS_KEY", "key.pem"), ("TLS_BIND", "localhost")]).is_err());
+    }
+
+    #[test]
+    fn test_file_bind_kept_even_when_default() {
+        assert_eq!(Config::from_file("", "127.0.0.1:8000").unwrap().server.bind, vec!["127.0.0.1:8000".to_string()]);
+        assert_eq!(Config::from_file("[server]\npublic_url = \"https://a.test\"\n", "127.0.0.1:8000").unwrap().server.bind, vec!["127.0.0.1:8000".to_string()]);
+        assert_eq!(Config::from_file("[server]\nbind = [\"0.0.0.0:9000\"]\n", "127.0.0.1:8000").unwrap().server.bind, vec!["0.0.0.0:9000".to_string()]);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = Config::from_toml("[storage]\ndata_dir = \"data\"\n\n[rate_limit]\nform_posts_per_minute = 3\n").unwrap();
+        assert_eq!(config.storage.data_dir, PathBuf::from("data"));
+        assert_eq!(config.rate_limit.form_posts_per_minute, 3);
+        assert_eq!(config.cors, CorsConfig::default());
+        assert_eq!(Config::from_toml(&config.to_toml()).unwrap(), config);
+    }
+}
@@ -0,0 +1,269 @@
+// Cross-origin request policy
+// Same-origin requests (the site's own forms, the desktop app's webview) are
+// always let through. Cross-origin ones are only answered for origins on the
+// allowlist, and only on the routes, methods and request headers listed for
+// them; a cross-origin POST from anywhere else is refused before it reaches a
+// handler. The admin and view routes (and the admin's /api/quotes) are never
+// opened cross-origin, whatever the configuration says.
+//
+// Configuration ([cors] in config.rs):
+//   origins - Origins allowed cross-origin requests
+//             (e.g. "https://help.southcitycomputer.com"), or "*" for any.
+//             Defaults to none.
+//   routes  - "path METHODS headers" rules, e.g. "/api/contact POST content-type"
+//             or "/* GET". A path ending in "*" is a prefix; the first match wins.
+//   max_age - Seconds browsers may cache a preflight. Defaults to 3600.
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse};
+use std::sync::OnceLock;
+
+use crate::config;
+
+/// Never opened cross-origin: the admin pages and API, and customers' records
+const EXCLUDED: &[&str] = &["/contact-admin", "/view/", "/api/quotes", "/api/overlay", "/metrics"];
+
+#[derive(Debug, PartialEq)]
+struct Rule {
+    path: String,
+    methods: Vec<Method>,
+    /// Lowercase request header names a cross-origin request may send
+    headers: Vec<String>,
+}
+
+impl Rule {
+    fn matches(&self, path: &str) -> bool {
+        match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.path,
+        }
+    }
+
+    fn allows_method(&self, method: &Method) -> bool {
+        self.methods.contains(method) || (method == Method::HEAD && self.methods.contains(&Method::GET))
+    }
+}
+
+struct Config {
+    /// None allows any origin
+    origins: Option<Vec<String>>,
+    rules: Vec<Rule>,
+    max_age: u64,
+}
+
+impl Config {
+    fn rule_for(&self, path: &str) -> Option<&Rule> {
+        if EXCLUDED.iter().any(|excluded| path == excluded.trim_end_matches('/') || path.starts_with(excluded)) {
+            return None;
+        }
+        self.rules.iter().find(|rule| rule.matches(path))
+    }
+
+    fn allows_origin(&self, origin: &str) -> bool {
+        self.origins.as_ref().is_none_or(|origins| origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)))
+    }
+}
+
+fn parse_origins<S: AsRef<str>>(entries: &[S]) -> Option<Vec<String>> {
+    let origins: Vec<String> =
+        entries.iter().map(|o| o.as_ref().trim().trim_end_matches('/').to_string()).filter(|o| !o.is_empty()).collect();
+    if origins.iter().any(|o| o == "*") {
+        return None;
+    }
+    Some(origins)
+}
+
+fn parse_routes<S: AsRef<str>>(entries: &[S]) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for entry in entries.iter().map(|e| e.as_ref().trim()).filter(|e| !e.is_empty()) {
+        let mut parts = entry.split_whitespace();
+        let path = parts.next().unwrap_or("");
+        let methods: Option<Vec<Method>> =
+            parts.next().unwrap_or("").split(',').map(|m| m.trim().to_uppercase().parse::<Method>().ok()).collect();
+        let headers = parts.next().unwrap_or("").split(',').map(|h| h.trim().to_ascii_lowercase()).filter(|h| !h.is_empty()).collect();
+        match methods {
+            Some(methods) if path.starts_with('/') && !methods.is_empty() => rules.push(Rule { path: path.to_string(), methods, headers }),
+            _ => log::warn!("Ignoring invalid CORS route: {}", entry),
+        }
+    }
+    rules
+}
+
+fn config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let settings = &config::get().cors;
+        Config { origins: parse_origins(&settings.origins), rules: parse_routes(&settings.routes), max_age: settings.max_age }
+    })
+}
+
+/// Whether `origin` ("https://host:port") names the host the request was sent to
+fn same_origin(origin: &str, host: &str) -> bool {
+    origin.split_once("://").is_some_and(|(_, authority)| authority.eq_ignore_ascii_case(host))
+}
+
+fn header_str(req: &ServiceRequest, name: header::HeaderName) -> Option<&str> {
+    req.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Answer for a preflight: 204 with what the rule allows, or 403
+fn preflight(config: &Config, rule: Option<&Rule>, origin: &str, req: &ServiceRequest) -> HttpResponse {
+    let requested_method = header_str(req, header::ACCESS_CONTROL_REQUEST_METHOD).and_then(|m| m.parse::<Method>().ok());
+    let requested_headers: Vec<String> = header_str(req, header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .unwrap_or("")
+        .split(',')
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty())
+        .collect();
+    let allowed = rule.filter(|rule| {
+        config.allows_origin(origin)
+            && requested_method.as_ref().is_some_and(|method| rule.allows_method(method))
+            && requested_headers.iter().all(|h| rule.headers.contains(h))
+    });
+    let Some(rule) = allowed else {
+        return HttpResponse::Forbidden().insert_header((header::VARY, "Origin")).body("Cross-origin request not allowed");
+    };
+
+    let methods: Vec<&str> = rule.methods.iter().map(Method::as_str).collect();
+    let mut response = HttpResponse::NoContent();
+    response
+        .insert_header((header::ACCESS_CONTROL_ALLOW_ORIGIN, origin))
+        .insert_header((header::ACCESS_CONTROL_ALLOW_METHODS, methods.join(", ")))
+        .insert_header((header::ACCESS_CONTROL_MAX_AGE, config.max_age.to_string()))
+        .insert_header((header::VARY, "Origin"));
+    if !rule.headers.is_empty() {
+        response.insert_header((header::ACCESS_CONTROL_ALLOW_HEADERS, rule.headers.join(", ")));
+    }
+    response.finish()
+}
+
+/// Middleware applying the CORS policy to every request
+pub async fn cors(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(origin) = header_str(&req, header::ORIGIN).map(str::to_string) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    if same_origin(&origin, req.connection_info().host()) {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let config = config();
+    let rule = config.rule_for(req.path());
+    if req.method() == Method::OPTIONS && req.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD) {
+        let response = preflight(config, rule, &origin, &req);
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    let allowed = rule.is_some_and(|rule| config.allows_origin(&origin) && rule.allows_method(req.method()));
+    if !allowed {
+        // Reads just get no CORS headers, so the browser hides the response;
+        // anything that could change state is refused outright
+        if req.method() == Method::GET || req.method() == Method::HEAD {
+            return Ok(next.call(req).await?.map_into_left_body());
+        }
+        let response = HttpResponse::Forbidden().body("Cross-origin request not allowed");
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    let mut res = next.call(req).await?;
+    if let Ok(value) = HeaderValue::from_str(&origin) {
+        res.headers_mut().insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+        res.headers_mut().append(header::VARY, HeaderValue::from_static("Origin"));
+    }
+    Ok(res.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware::from_fn, web, App};
+
+    fn test_config(origins: &str) -> Config {
+        let defaults = config::CorsConfig::default();
+        Config { origins: parse_origins(&origins.split(',').collect::<Vec<_>>()), rules: parse_routes(&defaults.routes), max_age: defaults.max_age }
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let rules = parse_routes(&["/api/contact POST,put Content-Type,X-Custom", "bogus GET", "/x NOPE@", "/* GET"]);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].methods, vec![Method::POST, Method::PUT]);
+        assert_eq!(rules[0].headers, vec!["content-type".to_string(), "x-custom".to_string()]);
+        assert!(rules[1].matches("/anything/here"));
+    }
+
+    #[test]
+    fn test_admin_and_view_excluded() {
+        let config = test_config("*");
+        assert!(config.rule_for("/contact-admin").is_none());
+        assert!(config.rule_for("/contact-admin/quotes").is_none());
+        assert!(config.rule_for("/view/ab12cd34").is_none());
+        assert!(config.rule_for("/api/quotes/q1/invoice").is_none());
+        assert_eq!(config.rule_for("/api/contact").map(|r| r.path.as_str()), Some("/api/contact"));
+        assert_eq!(config.rule_for("/css/style.min.css").map(|r| r.path.as_str()), Some("/*"));
+    }
+
+    #[test]
+    fn test_allows_origin() {
+        let config = test_config("https://help.southcitycomputer.com/, https://example.org");
+        assert!(config.allows_origin("https://help.southcitycomputer.com"));
+        assert!(!config.allows_origin("https://evil.example"));
+        assert!(test_config("*").allows_origin("https://evil.example"));
+        assert!(!test_config("").allows_origin("https://southcitycomputer.com"));
+        assert!(same_origin("http://127.0.0.1:9000", "127.0.0.1:9000"));
+        assert!(!same_origin("https://evil.example", "southcitycomputer.com"));
+    }
+
+    #[actix_web::test]
+    async fn test_cross_origin_post_refused() {
+        let app = init_service(
+            App::new().wrap(from_fn(cors)).route("/api/contact", web::post().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+        let cross = TestRequest::post().uri("/api/contact").insert_header((header::ORIGIN, "https://evil.example")).to_request();
+        assert_eq!(call_service(&app, cross).await.status(), StatusCode::FORBIDDEN);
+
+        let same = TestRequest::post()
+            .uri("/api/contact")
+            .insert_header((header::HOST, "southcitycomputer.com"))
+            .insert_header((header::ORIGIN, "https://southcitycomputer.com"))
+            .to_request();
+        assert_eq!(call_service(&app, same).await.status(), StatusCode::OK);
+
+        let preflight = TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/api/contact")
+            .insert_header((header::ORIGIN, "https://evil.example"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "POST"))
+            .to_request();
+        let response = call_service(&app, preflight).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[test]
+    fn test_preflight_for_allowed_origin() {
+        let config = test_config("https://help.southcitycomputer.com");
+        let request = |method: &str, headers: &str| {
+            TestRequest::default()
+                .method(Method::OPTIONS)
+                .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, method))
+                .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, headers))
+                .to_srv_request()
+        };
+        let origin = "https://help.southcitycomputer.com";
+        let rule = config.rule_for("/api/contact");
+        let response = preflight(&config, rule, origin, &request("POST", "Content-Type"));
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), origin);
+        assert_eq!(response.headers().get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap(), "POST");
+        assert_eq!(preflight(&config, rule, origin, &request("DELETE", "")).status(), StatusCode::FORBIDDEN);
+        assert_eq!(preflight(&config, rule, origin, &request("POST", "x-admin")).status(), StatusCode::FORBIDDEN);
+        assert_eq!(preflight(&config, config.rule_for("/view/abc"), origin, &request("GET", "")).status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -1,18 +1,9 @@
 // South City Computer - Desktop Application
 // Uses wry/tao to wrap the web server in a native window
 
-use actix_cors::Cors;
-use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, middleware, http::header};
-use base64::Engine;
-use chrono::Local;
-use mime_guess::from_path;
-use printpdf::*;
-use rust_embed::RustEmbed;
-use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::net::TcpListener;
-use std::path::Path;
+use actix_web::HttpServer;
+use scc_server::{config, logging};
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::sync::mpsc;
 use std::thread;
 use sys_locale::get_locale;
@@ -21,83 +12,56 @@ use tao::{
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
 };
-use tokio;
-use uuid::Uuid;
 use wry::WebViewBuilder;
 
-// Include all the shared code from main.rs
-include!("shared.rs");
-
-fn find_available_port() -> u16 {
-    TcpListener::bind("127.0.0.1:0")
-        .expect("Failed to bind to port")
-        .local_addr()
-        .expect("Failed to get local address")
-        .port()
-}
-
 fn main() {
-    let port = find_available_port();
+    // Loopback on a free port unless the config says otherwise
+    config::init("scc-desktop", "127.0.0.1:0", "");
+    let config = config::get();
+    logging::init(&config.log);
 
     // Detect system language
     let system_lang = get_locale()
         .map(|l| if l.starts_with("es") { "es" } else { "en" })
         .unwrap_or("en");
 
-    // Pass system language as URL parameter for initial load
-    let url = format!("http://127.0.0.1:{}/?syslang={}", port, system_lang);
-
-    // Channel to signal when server is ready
+    // Channel to send the bound address once the server is ready
     let (tx, rx) = mpsc::channel();
 
     // Start web server in background thread
-    let server_url = url.clone();
     thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let bind_addr = format!("127.0.0.1:{}", port);
-
             println!("╔═══════════════════════════════════════════════════════════╗");
             println!("║     SOUTH CITY COMPUTER - Desktop Application             ║");
             println!("╠═══════════════════════════════════════════════════════════╣");
             println!("║  All assets embedded in binary - zero disk reads          ║");
             println!("╚═══════════════════════════════════════════════════════════╝");
             println!();
-            println!("Starting local server on http://{}", bind_addr);
-
-            let server = HttpServer::new(|| {
-                let cors = Cors::default()
-                    .allow_any_origin()
-                    .allow_any_method()
-                    .allow_any_header()
-                    .max_age(3600);
-
-                App::new()
-                    .wrap(cors)
-                    .wrap(middleware::Compress::default())
-                    .route("/api/contact", web::post().to(handle_contact))
-                    .route("/api/service-inquiry", web::post().to(handle_service_inquiry))
-                    .route("/api/honeypot", web::post().to(handle_honeypot))
-                    .route("/view/{id}", web::get().to(view_submission))
-                    .route("/view/{id}/pdf", web::get().to(download_pdf))
-                    .route("/whitepaper/pdf/{lang}", web::get().to(download_whitepaper_pdf))
-                    .route("/contact-admin", web::get().to(contact_admin))
-                    .route("/health", web::get().to(health_check))
-                    .route("/", web::get().to(serve_index))
-                    .default_service(web::get().to(serve_embedded))
-            })
-            .bind(&bind_addr)
-            .expect("Failed to bind server");
+            let mut server = HttpServer::new(scc_server::app);
+            for addr in &config.server.bind {
+                server = server.bind(addr).expect("Failed to bind server");
+            }
+            for addr in server.addrs() {
+                println!("Starting local server on http://{}", addr);
+            }
 
             // Signal that server is ready
-            tx.send(()).unwrap();
+            tx.send(server.addrs()[0]).unwrap();
 
             server.run().await.unwrap();
         });
     });
 
     // Wait for server to be ready
-    rx.recv().unwrap();
+    let mut addr = rx.recv().unwrap();
+    // Bound to every interface: the webview still talks to loopback
+    if addr.ip().is_unspecified() {
+        addr.set_ip(if addr.is_ipv6() { Ipv6Addr::LOCALHOST.into() } else { Ipv4Addr::LOCALHOST.into() });
+    }
+
+    // Pass system language as URL parameter for initial load
+    let url = format!("http://{}/?syslang={}", addr, system_lang);
 
     // Give server a moment to fully initialize
     thread::sleep(std::time::Duration::from_millis(100));
@@ -0,0 +1,273 @@
+// Liveness and readiness
+// /health/live answers as long as the process can handle requests at all.
+// /health/ready answers 503 when this instance shouldn't be sent traffic:
+//   storage - the data directory (and every CSV store in it) accepts writes
+//   disk    - data_dir has at least health.min_free_mb free
+//   backlog - no more than health.max_in_flight requests are being handled
+//   config  - the config file and environment still load, so a restart would
+//             come back up; outdated CSV schemas, a missing accounts file or
+//             overlay directory are reported as warnings
+// While scc-server is shutting down, /health/ready answers 503 "stopping".
+// The checks' details go only to the clients allowed to scrape /metrics
+// (metrics.allow or metrics.token); anyone else gets the status alone. The
+// storage probe writes a file, so its result is reused for a few seconds.
+// /health/live reports the version from Cargo.toml, the build time and how
+// many assets are being served. /health keeps its old answer for existing
+// monitors.
+//
+// Configuration ([health] in config.rs):
+//   min_free_mb   - Free space needed in data_dir, MiB (HEALTH_MIN_FREE_MB)
+//   max_in_flight - Concurrent requests before reporting busy; 0 for no limit
+//                   (HEALTH_MAX_IN_FLIGHT)
+
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::{self, StorageBackend};
+use crate::{asset_http, assets, lifecycle, metrics, overlay, records};
+
+const MIB: u64 = 1024 * 1024;
+
+/// How long a storage probe's result is reused
+const STORAGE_PROBE_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+struct Check {
+    ok: bool,
+    detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+impl Check {
+    fn new(ok: bool, detail: impl Into<String>) -> Check {
+        Check { ok, detail: detail.into(), warnings: Vec::new() }
+    }
+}
+
+#[derive(Serialize)]
+struct Status {
+    status: &'static str,
+    version: &'static str,
+    build_time: String,
+    uptime_seconds: u64,
+    assets: usize,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    checks: BTreeMap<&'static str, Check>,
+}
+
+fn started() -> Instant {
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+    *STARTED.get_or_init(Instant::now)
+}
+
+fn status(status: &'static str, checks: BTreeMap<&'static str, Check>) -> Status {
+    Status {
+        status,
+        version: env!("CARGO_PKG_VERSION"),
+        build_time: DateTime::<Utc>::from(asset_http::build_time()).to_rfc3339(),
+        uptime_seconds: started().elapsed().as_secs(),
+        assets: assets::source().paths().len(),
+        checks,
+    }
+}
+
+/// Write, then remove, a file in `dir`, and open every existing store there
+/// for appending
+fn check_storage(dir: &Path) -> Check {
+    let probe = dir.join(format!(".ready-{}", uuid::Uuid::new_v4().simple()));
+    let written = OpenOptions::new().write(true).create_new(true).open(&probe).and_then(|mut file| file.write_all(b"ok\n"));
+    let _ = fs::remove_file(&probe);
+    if let Err(e) = written {
+        return Check::new(false, format!("data directory is not writable: {}", e));
+    }
+    for schema in records::ALL_SCHEMAS {
+        match OpenOptions::new().append(true).open(schema.path()) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Check::new(false, format!("{} is not writable: {}", schema.file, e)),
+        }
+    }
+    Check::new(true, "data directory and CSV stores are writable")
+}
+
+/// check_storage(), at most once per STORAGE_PROBE_TTL, so polling
+/// /health/ready doesn't create a file per request
+fn check_storage_cached(dir: &Path) -> Check {
+    static LAST: Mutex<Option<(Instant, Check)>> = Mutex::new(None);
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    match &*last {
+        Some((at, check)) if at.elapsed() < STORAGE_PROBE_TTL => check.clone(),
+        _ => {
+            let check = check_storage(dir);
+            *last = Some((Instant::now(), check.clone()));
+            check
+        }
+    }
+}
+
+/// Space available to this process on the filesystem holding `dir`
+#[cfg(unix)]
+fn free_bytes(dir: &Path) -> io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and statvfs only writes into `stats`
+    if unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: statvfs succeeded, so it filled `stats` in
+    let stats = unsafe { stats.assume_init() };
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_bytes(_dir: &Path) -> io::Result<u64> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "not available on this platform"))
+}
+
+fn check_disk(dir: &Path, min_free_mb: u64) -> Check {
+    match free_bytes(dir) {
+        Ok(free) if free < min_free_mb.saturating_mul(MIB) => Check::new(false, format!("{} MiB free, below {} MiB", free / MIB, min_free_mb)),
+        Ok(free) => Check::new(true, format!("{} MiB free", free / MIB)),
+        // Can't tell: don't take the instance out of rotation for it
+        Err(e) => Check::new(true, format!("free space unknown: {}", e)),
+    }
+}
+
+fn check_backlog(max_in_flight: u64) -> Check {
+    let in_flight = metrics::in_flight().max(0) as u64;
+    let detail = format!("{} requests in flight, {} tarpitted decoy responses", in_flight, metrics::tarpit_streams());
+    Check::new(max_in_flight == 0 || in_flight <= max_in_flight, detail)
+}
+
+fn check_config() -> Check {
+    let settings = config::get();
+    let mut check = match config::check_reload() {
+        Ok(()) => Check::new(true, "configuration loads"),
+        Err(e) => Check::new(false, format!("configuration no longer loads, a restart would fail: {}", e)),
+    };
+    for schema in records::outdated_schemas() {
+        check.warnings.push(format!("{} uses an older schema; run `scc-server migrate`", schema.file));
+    }
+    if !settings.accounts_path().is_file() {
+        check.warnings.push("no accounts file: the admin pages can't be logged into".to_string());
+    }
+    if overlay::get().is_some_and(|overlay| !overlay.dir().is_dir()) {
+        check.warnings.push("overlay.dir does not exist".to_string());
+    }
+    check
+}
+
+fn readiness() -> BTreeMap<&'static str, Check> {
+    let settings = config::get();
+    let data_dir = &settings.storage.data_dir;
+    let mut checks = BTreeMap::new();
+    match settings.storage.backend {
+        StorageBackend::Csv => checks.insert("storage", check_storage_cached(data_dir)),
+    };
+    checks.insert("disk", check_disk(data_dir, settings.health.min_free_mb));
+    checks.insert("backlog", check_backlog(settings.health.max_in_flight));
+    checks.insert("config", check_config());
+    checks
+}
+
+async fn live() -> HttpResponse {
+    HttpResponse::Ok().insert_header((header::CACHE_CONTROL, "no-store")).json(status("alive", BTreeMap::new()))
+}
+
+async fn ready(req: HttpRequest) -> HttpResponse {
+    let checks = readiness();
+    let is_ready = checks.values().all(|check| check.ok);
+    let (mut response, state) = if lifecycle::stopping() {
+        (HttpResponse::ServiceUnavailable(), "stopping")
+    } else if is_ready {
+        (HttpResponse::Ok(), "ready")
+    } else {
+        (HttpResponse::ServiceUnavailable(), "not_ready")
+    };
+    for (name, check) in checks.iter().filter(|(_, check)| !check.ok) {
+        log::warn!("Not ready: {}: {}", name, check.detail);
+    }
+    response.insert_header((header::CACHE_CONTROL, "no-store"));
+    if metrics::scraper(&req) {
+        response.json(status(state, checks))
+    } else {
+        response.json(serde_json::json!({ "status": state }))
+    }
+}
+
+pub fn configure_health(cfg: &mut web::ServiceConfig) {
+    started();
+    cfg.route("/health/live", web::get().to(live)).route("/health/ready", web::get().to(ready));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+    use actix_web::App;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("scc-health-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_storage_check() {
+        let dir = temp_dir("storage");
+        assert!(check_storage(&dir).ok);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0, "probe file left behind");
+        assert!(!check_storage(&dir.join("missing")).ok);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_disk_check() {
+        let dir = temp_dir("disk");
+        assert!(check_disk(&dir, 0).ok);
+        if free_bytes(&dir).is_ok() {
+            assert!(!check_disk(&dir, u64::MAX / MIB).ok);
+            assert!(!check_disk(&dir, u64::MAX).ok);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backlog_check() {
+        assert!(check_backlog(0).ok);
+        assert!(check_backlog(u64::MAX).ok);
+    }
+
+    #[actix_web::test]
+    async fn test_endpoints_report_build() {
+        let app = init_service(App::new().configure(configure_health)).await;
+        let live: serde_json::Value = read_body_json(call_service(&app, TestRequest::get().uri("/health/live").to_request()).await).await;
+        assert_eq!(live["status"], "alive");
+        assert_eq!(live["version"], env!("CARGO_PKG_VERSION"));
+        assert!(live["assets"].as_u64().unwrap() > 0);
+
+        let local = TestRequest::get().uri("/health/ready").peer_addr("127.0.0.1:40000".parse().unwrap());
+        let ready = call_service(&app, local.to_request()).await;
+        let ok = ready.status().is_success();
+        let ready: serde_json::Value = read_body_json(ready).await;
+        assert_eq!(ready["status"], if ok { "ready" } else { "not_ready" });
+        for check in ["storage", "disk", "backlog", "config"] {
+            assert!(ready["checks"][check]["ok"].is_boolean(), "{} missing", check);
+        }
+
+        let remote = TestRequest::get().uri("/health/ready").peer_addr("203.0.113.9:40000".parse().unwrap());
+        let ready: serde_json::Value = read_body_json(call_service(&app, remote.to_request()).await).await;
+        assert_eq!(ready, serde_json::json!({ "status": if ok { "ready" } else { "not_ready" } }));
+    }
+}
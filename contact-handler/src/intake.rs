@@ -0,0 +1,301 @@
+// Service inquiry intake form PDF
+// The question and option labels come from the service page's own form markup
+// (services/{service_type}.html), so the printed form always matches what the
+// customer saw. Answers the page no longer asks about are still printed, with
+// a label derived from the field name.
+
+use crate::pdf_layout::{PageStyle, PdfLayout, PdfResult, Weight};
+use crate::{assets, scan, ServiceInquiryRecord};
+
+/// Fields shown in the contact section rather than as form answers
+const CONTACT_FIELDS: &[&str] = &["service_type", "name", "email", "phone", "details"];
+
+#[derive(Debug, PartialEq)]
+pub struct FormQuestion {
+    pub name: String,
+    pub label: String,
+    /// (value, label) pairs for radio buttons, checkboxes and selects
+    pub options: Vec<(String, String)>,
+}
+
+impl FormQuestion {
+    fn option_label<'a>(&'a self, value: &'a str) -> &'a str {
+        self.options
+            .iter()
+            .find(|(v, _)| v == value)
+            .map(|(_, label)| label.as_str())
+            .unwrap_or(value)
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Value of `name="..."` inside a single tag
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// Text of the first `<label ...>text</label>` at or after `from`
+fn label_text(html: &str, from: usize) -> Option<(usize, String)> {
+    let start = from + html[from..].find("<label")?;
+    let text_start = start + html[start..].find('>')? + 1;
+    let text_len = html[text_start..].find("</label>")?;
+    let text = decode_entities(html[text_start..text_start + text_len].trim());
+    Some((text_start + text_len, text))
+}
+
+fn label_for(html: &str, id: &str) -> Option<String> {
+    let needle = format!("<label for=\"{}\"", id);
+    let start = html.find(&needle)?;
+    label_text(html, start).map(|(_, text)| text)
+}
+
+/// Questions from every `form-group` on a service page, in page order
+pub fn parse_form_schema(html: &str) -> Vec<FormQuestion> {
+    let mut questions: Vec<FormQuestion> = Vec::new();
+
+    for group in html.split("class=\"form-group\"").skip(1) {
+        let Some((_, label)) = label_text(group, 0) else { continue };
+        let Some(name) = attr(group, "name") else { continue };
+        let name = name.trim_end_matches("[]");
+        if CONTACT_FIELDS.contains(&name) || questions.iter().any(|q| q.name == name) {
+            continue;
+        }
+
+        let mut options = Vec::new();
+        for tag in group.split("<input").skip(1) {
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            if let (Some(id), Some(value)) = (attr(tag, "id"), attr(tag, "value")) {
+                if let Some(text) = label_for(group, id) {
+                    options.push((value.to_string(), text));
+                }
+            }
+        }
+        for option in group.split("<option").skip(1) {
+            let Some(value) = attr(option, "value").filter(|v| !v.is_empty()) else { continue };
+            let Some(text_start) = option.find('>') else { continue };
+            let text = &option[text_start + 1..];
+            let text = &text[..text.find("</option>").unwrap_or(text.len())];
+            options.push((value.to_string(), decode_entities(text.trim())));
+        }
+
+        questions.push(FormQuestion { name: name.to_string(), label, options });
+    }
+
+    questions
+}
+
+/// Schema for a service type, or an empty one if it has no page
+pub fn form_schema(service_type: &str) -> Vec<FormQuestion> {
+    if service_type.is_empty() || !service_type.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Vec::new();
+    }
+    assets::source()
+        .get(&format!("services/{}.html", service_type))
+        .map(|file| parse_form_schema(&String::from_utf8_lossy(&file.data)))
+        .unwrap_or_default()
+}
+
+/// "computer-repair" -> "Computer Repair", "data_backup" -> "Data Backup"
+pub fn title_case(slug: &str) -> String {
+    slug.split(['-', '_'])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn answer_text(value: &serde_json::Value, question: Option<&FormQuestion>) -> String {
+    let label = |v: &str| question.map(|q| q.option_label(v).to_string()).unwrap_or_else(|| v.to_string());
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| match item.as_str() {
+                Some(s) => label(s),
+                None => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        serde_json::Value::String(s) => label(s),
+        serde_json::Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// (label, answer) rows: schema questions first in page order, then anything
+/// else the inquiry recorded.
+pub fn labelled_answers(inquiry: &ServiceInquiryRecord, schema: &[FormQuestion]) -> Vec<(String, String)> {
+    let answers = match inquiry.answers.as_object() {
+        Some(obj) => obj,
+        None => return Vec::new(),
+    };
+
+    let mut rows: Vec<(String, String)> = schema
+        .iter()
+        .filter_map(|q| answers.get(&q.name).map(|v| (q.label.clone(), answer_text(v, Some(q)))))
+        .collect();
+
+    for (key, value) in answers {
+        if CONTACT_FIELDS.contains(&key.as_str()) || schema.iter().any(|q| &q.name == key) {
+            continue;
+        }
+        rows.push((title_case(key), answer_text(value, None)));
+    }
+
+    rows.retain(|(_, answer)| !answer.is_empty());
+    rows
+}
+
+pub fn generate_service_inquiry_pdf(inquiry: &ServiceInquiryRecord) -> PdfResult<Vec<u8>> {
+    let service = title_case(&inquiry.service_type);
+    let mut pdf = PdfLayout::new("Service Intake Form", PageStyle {
+        header_title: "SOUTH CITY COMPUTER".to_string(),
+        header_subtitle: format!("Service Intake Form - {}", service),
+        footer: "South City Computer | Puerto Morelos, Mexico | southcitycomputer.com".to_string(),
+        page_label: "Page".to_string(),
+    })?;
+
+    let (view_url, caption) = scan::view_link(&inquiry.id);
+    pdf.qr_code(&view_url, &caption)?;
+    pdf.text(&format!("Reference ID: {}    Submitted: {}", inquiry.id, inquiry.timestamp), 10.0, Weight::Regular);
+    pdf.space(6.0);
+
+    pdf.heading("Customer");
+    pdf.field("NAME", &inquiry.name);
+    pdf.field("EMAIL", &inquiry.email);
+    pdf.field("PHONE", if inquiry.phone.is_empty() { "Not provided" } else { &inquiry.phone });
+
+    pdf.heading("Service Request");
+    pdf.field("SERVICE", &service);
+    for (label, answer) in labelled_answers(inquiry, &form_schema(&inquiry.service_type)) {
+        pdf.field(&label.to_uppercase(), &answer);
+    }
+    pdf.field("DETAILS", if inquiry.details.is_empty() { "None provided" } else { &inquiry.details });
+
+    pdf.heading("Technician Notes");
+    for _ in 0..8 {
+        pdf.write_in_line("");
+    }
+
+    pdf.space(4.0);
+    pdf.heading("Sign-off");
+    pdf.write_in_line("Technician");
+    pdf.write_in_line("Customer signature");
+    pdf.write_in_line("Date");
+
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM: &str = r#"
+        <input type="hidden" name="service_type" value="computer-repair">
+        <div class="form-group">
+            <label>Device Type</label>
+            <div class="radio-option">
+                <input type="radio" id="device-laptop" name="device_type" value="laptop" required>
+                <label for="device-laptop">Laptop</label>
+            </div>
+            <div class="radio-option">
+                <input type="radio" id="device-allinone" name="device_type" value="all-in-one">
+                <label for="device-allinone">All-in-One</label>
+            </div>
+        </div>
+        <div class="form-group">
+            <label for="brand">Brand</label>
+            <select id="brand" name="brand" required>
+                <option value="">Select brand...</option>
+                <option value="microsoft">Microsoft Surface</option>
+            </select>
+        </div>
+        <div class="form-group">
+            <label>What's the problem?</label>
+            <input type="checkbox" id="size-small" name="problem[]" value="small">
+            <label for="size-small">Small (< 10K lines)</label>
+            <input type="checkbox" id="problem-other" name="problem[]" value="other">
+            <label for="problem-other">Other &amp; misc</label>
+        </div>
+        <div class="form-group">
+            <label for="repair-name">Your Name</label>
+            <input type="text" id="repair-name" name="name" required>
+        </div>
+    "#;
+
+    fn inquiry(answers: serde_json::Value) -> ServiceInquiryRecord {
+        ServiceInquiryRecord {
+            id: "abc12345".to_string(),
+            timestamp: "2026-01-01 10:00:00".to_string(),
+            service_type: "computer-repair".to_string(),
+            name: "Ana".to_string(),
+            email: "ana@example.com".to_string(),
+            phone: String::new(),
+            details: String::new(),
+            answers,
+        }
+    }
+
+    #[test]
+    fn test_parse_form_schema() {
+        let schema = parse_form_schema(FORM);
+        assert_eq!(schema.len(), 3);
+        assert_eq!(schema[0].name, "device_type");
+        assert_eq!(schema[0].label, "Device Type");
+        assert_eq!(schema[0].option_label("all-in-one"), "All-in-One");
+        assert_eq!(schema[1].option_label("microsoft"), "Microsoft Surface");
+        assert_eq!(schema[1].options.len(), 1);
+        assert_eq!(schema[2].name, "problem");
+        assert_eq!(schema[2].option_label("small"), "Small (< 10K lines)");
+        assert_eq!(schema[2].option_label("other"), "Other & misc");
+    }
+
+    #[test]
+    fn test_labelled_answers_use_schema_then_extras() {
+        let schema = parse_form_schema(FORM);
+        let record = inquiry(serde_json::json!({
+            "problem": ["small", "other"],
+            "device_type": "laptop",
+            "name": "Ana",
+            "legacy_field": "kept",
+            "brand": "",
+        }));
+        let rows = labelled_answers(&record, &schema);
+        assert_eq!(rows, vec![
+            ("Device Type".to_string(), "Laptop".to_string()),
+            ("What's the problem?".to_string(), "Small (< 10K lines), Other & misc".to_string()),
+            ("Legacy Field".to_string(), "kept".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_embedded_service_pages_have_schemas() {
+        let schema = form_schema("computer-repair");
+        assert!(schema.iter().any(|q| q.name == "device_type" && !q.options.is_empty()));
+        assert!(form_schema("../index").is_empty());
+        assert!(form_schema("no-such-service").is_empty());
+    }
+
+    #[test]
+    fn test_generate_service_inquiry_pdf() {
+        let record = inquiry(serde_json::json!({ "device_type": "laptop", "urgency": "asap" }));
+        let pdf = generate_service_inquiry_pdf(&record).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
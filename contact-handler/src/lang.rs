@@ -0,0 +1,176 @@
+// Language negotiation
+// The site ships English pages with Spanish twins next to them
+// (projects.html / projects-es.html, blog/*-es.html, docs/*-es.html). Clean
+// URLs (/projects, /docs/) serve whichever twin the visitor reads: a ?lang=
+// or ?syslang= parameter (the desktop app's first load) first, then the
+// `lang` cookie the language switcher keeps, then Accept-Language. Explicit
+// .html paths are always served as asked, so they're what hreflang points at.
+
+use actix_web::http::header;
+use actix_web::HttpRequest;
+
+use crate::public_url;
+
+pub const COOKIE: &str = "lang";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Es,
+}
+
+impl Lang {
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Es => "es",
+        }
+    }
+
+    /// "es", "es-MX", "ES" -> Es; "en", "en-US" -> En
+    fn from_tag(tag: &str) -> Option<Lang> {
+        let primary = tag.trim().split(['-', '_']).next().unwrap_or("");
+        if primary.eq_ignore_ascii_case("es") {
+            Some(Lang::Es)
+        } else if primary.eq_ignore_ascii_case("en") {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+
+    /// The English or Spanish text, whichever this language reads
+    pub fn pick<'a>(self, en: &'a str, es: &'a str) -> &'a str {
+        match self {
+            Lang::En => en,
+            Lang::Es => es,
+        }
+    }
+}
+
+/// Preferred of English and Spanish by Accept-Language quality; the first
+/// listed wins a tie
+fn from_accept_language(value: &str) -> Option<Lang> {
+    let mut best: Option<(f32, Lang)> = None;
+    for item in value.split(',') {
+        let mut parts = item.split(';');
+        let Some(lang) = Lang::from_tag(parts.next().unwrap_or("")) else { continue };
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if q > 0.0 && best.is_none_or(|(best_q, _)| q > best_q) {
+            best = Some((q, lang));
+        }
+    }
+    best.map(|(_, lang)| lang)
+}
+
+fn from_query(query: &str) -> Option<Lang> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "lang" || *key == "syslang")
+        .and_then(|(_, value)| Lang::from_tag(value))
+}
+
+pub fn negotiate(req: &HttpRequest) -> Lang {
+    from_query(req.query_string())
+        .or_else(|| req.cookie(COOKIE).and_then(|cookie| Lang::from_tag(cookie.value())))
+        .or_else(|| {
+            req.headers()
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(from_accept_language)
+        })
+        .unwrap_or(Lang::En)
+}
+
+/// Language of an embedded page, from its "-es" suffix
+pub fn page_lang(path: &str) -> Lang {
+    if path.ends_with("-es.html") {
+        Lang::Es
+    } else {
+        Lang::En
+    }
+}
+
+/// "projects-es.html" <-> "projects.html"; the other language's path for a page
+pub fn twin_path(path: &str) -> Option<String> {
+    let stem = path.strip_suffix(".html")?;
+    Some(match stem.strip_suffix("-es") {
+        Some(english) => format!("{}.html", english),
+        None => format!("{}-es.html", stem),
+    })
+}
+
+/// URLs of both language versions of a page as (hreflang, url), with the
+/// clean URL (which negotiates) as the x-default
+pub fn alternate_urls(page: &str, twin: &str) -> [(&'static str, String); 3] {
+    let (en, es) = if page_lang(page) == Lang::Es { (twin, page) } else { (page, twin) };
+    let clean = en.strip_suffix(".html").unwrap_or(en);
+    let clean = if clean == "index" { "" } else { clean.strip_suffix("/index").map(|dir| &clean[..dir.len() + 1]).unwrap_or(clean) };
+    let base = public_url();
+    [
+        ("en", format!("{}/{}", base, en)),
+        ("es", format!("{}/{}", base, es)),
+        ("x-default", format!("{}/{}", base, clean)),
+    ]
+}
+
+/// Link header naming both language versions of a page
+pub fn alternates(page: &str, twin: &str) -> String {
+    alternate_urls(page, twin)
+        .iter()
+        .map(|(hreflang, url)| format!("<{}>; rel=\"alternate\"; hreflang=\"{}\"", url, hreflang))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_from_accept_language() {
+        assert_eq!(from_accept_language("es-MX,es;q=0.9,en;q=0.8"), Some(Lang::Es));
+        assert_eq!(from_accept_language("fr-FR, en;q=0.5, es;q=0.4"), Some(Lang::En));
+        assert_eq!(from_accept_language("en;q=0.3, es-419;q=0.7"), Some(Lang::Es));
+        assert_eq!(from_accept_language("es;q=0, en"), Some(Lang::En));
+        assert_eq!(from_accept_language("de, fr"), None);
+    }
+
+    #[test]
+    fn test_negotiate_order() {
+        let req = TestRequest::with_uri("/projects?syslang=es")
+            .insert_header((header::ACCEPT_LANGUAGE, "en"))
+            .to_http_request();
+        assert_eq!(negotiate(&req), Lang::Es);
+        let req = TestRequest::default()
+            .insert_header((header::COOKIE, "lang=en"))
+            .insert_header((header::ACCEPT_LANGUAGE, "es"))
+            .to_http_request();
+        assert_eq!(negotiate(&req), Lang::En);
+        let req = TestRequest::default().insert_header((header::ACCEPT_LANGUAGE, "es-ES")).to_http_request();
+        assert_eq!(negotiate(&req), Lang::Es);
+        assert_eq!(negotiate(&TestRequest::default().to_http_request()), Lang::En);
+    }
+
+    #[test]
+    fn test_twin_path() {
+        assert_eq!(twin_path("projects.html").as_deref(), Some("projects-es.html"));
+        assert_eq!(twin_path("blog/whitepaper-es.html").as_deref(), Some("blog/whitepaper.html"));
+        assert_eq!(twin_path("css/style.min.css"), None);
+        assert_eq!(page_lang("docs/index-es.html"), Lang::Es);
+    }
+
+    #[test]
+    fn test_alternates() {
+        let link = alternates("docs/index-es.html", "docs/index.html");
+        assert!(link.contains("/docs/index.html>; rel=\"alternate\"; hreflang=\"en\""));
+        assert!(link.contains("/docs/index-es.html>; rel=\"alternate\"; hreflang=\"es\""));
+        assert!(link.ends_with("/docs/>; rel=\"alternate\"; hreflang=\"x-default\""));
+        assert!(alternates("projects.html", "projects-es.html").ends_with("/projects>; rel=\"alternate\"; hreflang=\"x-default\""));
+    }
+}
@@ -0,0 +1,1582 @@
+// South City Computer web server
+// Everything scc-server, scc-dev and scc-desktop serve: the form API, record
+// and admin pages, PDFs and the static site. The binaries only differ in where
+// assets come from (see assets.rs) and how they start up; each one serves app().
+
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{web, App, HttpRequest, HttpResponse, middleware, http::header};
+use base64::Engine;
+use chrono::Local;
+use mime_guess::from_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use uuid::Uuid;
+
+mod analytics;
+pub mod assets;
+pub mod config;
+mod decoys;
+pub mod lifecycle;
+pub mod live_reload;
+pub mod logging;
+mod metrics;
+pub mod overlay;
+pub mod tls;
+mod records;
+mod pdf_layout;
+mod intake;
+mod markdown_pdf;
+mod billing;
+mod scan;
+mod asset_http;
+mod lang;
+mod health;
+mod seo;
+mod security_headers;
+mod cors;
+mod rate_limit;
+
+pub use decoys::list_decoys;
+pub use records::{check_schemas, run_migrate};
+
+#[derive(Debug, Deserialize)]
+struct ContactForm {
+    name: String,
+    email: String,
+    phone: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceInquiry {
+    service_type: String,
+    name: String,
+    email: String,
+    phone: Option<String>,
+    details: Option<String>,
+    #[serde(flatten)]
+    answers: std::collections::HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiResponse {
+    success: bool,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ContactResponse {
+    success: bool,
+    message: String,
+    id: Option<String>,
+    view_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Submission {
+    id: String,
+    timestamp: String,
+    name: String,
+    email: String,
+    phone: String,
+    message: String,
+}
+
+struct ServiceInquiryRecord {
+    id: String,
+    timestamp: String,
+    service_type: String,
+    name: String,
+    email: String,
+    phone: String,
+    details: String,
+    answers: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct HoneypotAttempt {
+    username: String,
+    password: String,
+    // Fingerprint data sent from client
+    source: Option<String>,           // Which honeypot page (wordpress, django, phpmyadmin)
+    screen: Option<String>,           // Screen resolution
+    timezone: Option<String>,         // Timezone offset
+    language: Option<String>,         // Browser language
+    platform: Option<String>,         // OS platform
+    cookies: Option<bool>,            // Cookies enabled
+    dnt: Option<bool>,                // Do Not Track
+    webgl: Option<String>,            // WebGL renderer (GPU)
+    canvas_hash: Option<String>,      // Canvas fingerprint hash
+    touch: Option<bool>,              // Touch support
+    plugins: Option<String>,          // Browser plugins count
+}
+
+fn generate_short_id() -> String {
+    let uuid = Uuid::new_v4();
+    let hex = uuid.simple().to_string();
+    hex[..8].to_string()
+}
+
+/// Base URL the site is published at, for links in documents and headers
+/// (server.public_url, default https://southcitycomputer.com)
+fn public_url() -> &'static str {
+    &config::get().server.public_url
+}
+
+/// 429 for a client over the form submission limit (see rate_limit.rs)
+fn form_rate_limited(req: &HttpRequest) -> Option<HttpResponse> {
+    let retry_after = rate_limit::forms().check(rate_limit::client(req)?, std::time::Instant::now()).err()?;
+    Some(
+        HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", retry_after.as_secs().max(1).to_string()))
+            .json(ContactResponse {
+                success: false,
+                message: "Too many submissions, please try again in a minute".to_string(),
+                id: None,
+                view_url: None,
+            }),
+    )
+}
+
+async fn handle_contact(req: HttpRequest, form: web::Json<ContactForm>) -> HttpResponse {
+    if let Some(response) = form_rate_limited(&req) {
+        return response;
+    }
+    let id = generate_short_id();
+    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let submission = Submission {
+        id: id.clone(),
+        timestamp,
+        name: form.name.clone(),
+        email: form.email.clone(),
+        phone: form.phone.clone().unwrap_or_default(),
+        message: form.message.clone(),
+    };
+
+    let result = records::append_contact(&submission);
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ContactResponse {
+            success: true,
+            message: "Contact submitted successfully".to_string(),
+            id: Some(id.clone()),
+            view_url: Some(format!("/view/{}", id)),
+        }),
+        Err(e) => {
+            log::error!("Error writing contact to CSV: {}", e);
+            HttpResponse::InternalServerError().json(ContactResponse {
+                success: false,
+                message: "Failed to save contact".to_string(),
+                id: None,
+                view_url: None,
+            })
+        }
+    }
+}
+
+async fn handle_service_inquiry(req: HttpRequest, form: web::Json<ServiceInquiry>) -> HttpResponse {
+    if let Some(response) = form_rate_limited(&req) {
+        return response;
+    }
+    let id = generate_short_id();
+    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let inquiry = ServiceInquiryRecord {
+        id: id.clone(),
+        timestamp,
+        service_type: form.service_type.clone(),
+        name: form.name.clone(),
+        email: form.email.clone(),
+        phone: form.phone.clone().unwrap_or_default(),
+        details: form.details.clone().unwrap_or_default(),
+        answers: serde_json::to_value(&form.answers).unwrap_or_default(),
+    };
+
+    let result = records::append_service_inquiry(&inquiry);
+
+    match result {
+        Ok(_) => {
+            log::info!("Service inquiry saved: {} - {}", id, form.service_type);
+            HttpResponse::Ok().json(ContactResponse {
+                success: true,
+                message: "Service inquiry submitted successfully".to_string(),
+                id: Some(id.clone()),
+                view_url: None,
+            })
+        }
+        Err(e) => {
+            log::error!("Error writing service inquiry to CSV: {}", e);
+            HttpResponse::InternalServerError().json(ContactResponse {
+                success: false,
+                message: "Failed to save inquiry".to_string(),
+                id: None,
+                view_url: None,
+            })
+        }
+    }
+}
+
+/// One row of honeypot_attempts.csv, shared by the JavaScript honeypot pages
+/// and the server-side decoy routes.
+#[derive(Debug, Default)]
+struct HoneypotRecord {
+    timestamp: String,
+    source: String,
+    username: String,
+    password: String,
+    ip: String,
+    user_agent: String,
+    screen: String,
+    timezone: String,
+    language: String,
+    platform: String,
+    cookies: String,
+    dnt: String,
+    webgl: String,
+    canvas_hash: String,
+    touch: String,
+    plugins: String,
+    path: String,
+    headers: String,
+    body: String,
+}
+
+fn yes_no(value: Option<bool>) -> String {
+    if value.unwrap_or(false) { "yes" } else { "no" }.to_string()
+}
+
+// Get IP from request headers (check forwarded headers first)
+fn request_ip(req: &HttpRequest) -> String {
+    req.connection_info().realip_remote_addr()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn request_user_agent(req: &HttpRequest) -> String {
+    req.headers().get("User-Agent")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+async fn handle_honeypot(form: web::Json<HoneypotAttempt>, req: HttpRequest) -> HttpResponse {
+    let record = HoneypotRecord {
+        timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        source: form.source.clone().unwrap_or_else(|| "unknown".to_string()),
+        username: form.username.clone(),
+        password: form.password.clone(),
+        ip: request_ip(&req),
+        user_agent: request_user_agent(&req),
+        // Fingerprint data from client
+        screen: form.screen.clone().unwrap_or_default(),
+        timezone: form.timezone.clone().unwrap_or_default(),
+        language: form.language.clone().unwrap_or_default(),
+        platform: form.platform.clone().unwrap_or_default(),
+        cookies: yes_no(form.cookies),
+        dnt: yes_no(form.dnt),
+        webgl: form.webgl.clone().unwrap_or_default(),
+        canvas_hash: form.canvas_hash.clone().unwrap_or_default(),
+        touch: yes_no(form.touch),
+        plugins: form.plugins.clone().unwrap_or_default(),
+        path: req.path().to_string(),
+        ..Default::default()
+    };
+
+    match records::append_honeypot_attempt(&record) {
+        Ok(_) => {
+            log::info!("Honeypot triggered: {} / {} from {}", form.username, logging::password(&form.password), record.ip);
+            HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                message: "Logged".to_string(),
+            })
+        }
+        Err(e) => {
+            log::error!("Error writing honeypot attempt to CSV: {}", e);
+            HttpResponse::InternalServerError().json(ApiResponse {
+                success: false,
+                message: "Failed".to_string(),
+            })
+        }
+    }
+}
+
+fn find_submission_by_id(id: &str) -> Option<Submission> {
+    records::load_contacts().into_iter().find(|s| s.id == id)
+}
+
+fn find_service_inquiry_by_id(id: &str) -> Option<ServiceInquiryRecord> {
+    records::load_service_inquiries().into_iter().find(|s| s.id == id)
+}
+
+async fn view_submission(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    let lang = lang::negotiate(&req);
+
+    // First check regular contacts, then service inquiries
+    let (mut response, body) = if let Some(submission) = find_submission_by_id(&id) {
+        (HttpResponse::Ok(), generate_view_html(&submission, lang))
+    } else if let Some(inquiry) = find_service_inquiry_by_id(&id) {
+        (HttpResponse::Ok(), generate_service_inquiry_view_html(&inquiry, lang, &security_headers::nonce(&req)))
+    } else {
+        (HttpResponse::NotFound(), generate_not_found_html(lang))
+    };
+
+    response
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CONTENT_LANGUAGE, lang.code()))
+        .insert_header((header::VARY, "Accept-Language, Cookie"))
+        .body(body)
+}
+
+fn generate_service_inquiry_view_html(inquiry: &ServiceInquiryRecord, lang: lang::Lang, nonce: &str) -> String {
+    let t = |en, es| lang.pick(en, es);
+    // Format the answers nicely
+    let answers_html = if let Some(obj) = inquiry.answers.as_object() {
+        obj.iter()
+            .filter(|(k, _)| *k != "service_type" && *k != "name" && *k != "email" && *k != "phone" && *k != "details")
+            .map(|(k, v)| {
+                let value = match v {
+                    serde_json::Value::Array(arr) => arr.iter()
+                        .filter_map(|x| x.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    serde_json::Value::String(s) => s.clone(),
+                    _ => v.to_string(),
+                };
+                format!(
+                    r#"<div class="info-row"><span class="label">{}</span><span class="value">{}</span></div>"#,
+                    html_escape(&k.replace("_", " ")),
+                    html_escape(&value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_inquiry} - South City Computer</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #1a5276 0%, #2980b9 50%, #00bcd4 100%);
+            min-height: 100vh;
+            padding: 40px 20px;
+        }}
+        .container {{
+            max-width: 700px;
+            margin: 0 auto;
+        }}
+        .header {{
+            text-align: center;
+            margin-bottom: 32px;
+        }}
+        .logo {{
+            height: 60px;
+            margin-bottom: 16px;
+            filter: brightness(0) invert(1);
+        }}
+        h1 {{
+            color: #fff;
+            font-size: 1.5rem;
+            font-weight: 500;
+        }}
+        .service-badge {{
+            display: inline-block;
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            color: #fff;
+            padding: 8px 16px;
+            border-radius: 20px;
+            font-weight: 600;
+            text-transform: uppercase;
+            font-size: 0.85rem;
+            margin-top: 12px;
+        }}
+        .card {{
+            background: #fff;
+            border-radius: 16px;
+            padding: 32px;
+            box-shadow: 0 10px 40px rgba(0, 0, 0, 0.2);
+        }}
+        .info-row {{
+            display: flex;
+            justify-content: space-between;
+            padding: 16px 0;
+            border-bottom: 1px solid #eee;
+        }}
+        .info-row:last-child {{
+            border-bottom: none;
+        }}
+        .label {{
+            color: #666;
+            font-size: 0.9rem;
+            text-transform: capitalize;
+        }}
+        .value {{
+            color: #333;
+            font-weight: 500;
+            text-align: right;
+            max-width: 60%;
+            word-break: break-word;
+        }}
+        .section-title {{
+            color: #1a5276;
+            font-size: 1rem;
+            font-weight: 600;
+            margin: 24px 0 12px 0;
+            padding-top: 16px;
+            border-top: 2px solid #eee;
+        }}
+        .section-title:first-child {{
+            margin-top: 0;
+            padding-top: 0;
+            border-top: none;
+        }}
+        .message-box {{
+            background: #f8f9fa;
+            padding: 16px;
+            border-radius: 8px;
+            margin-top: 16px;
+            white-space: pre-wrap;
+            line-height: 1.6;
+        }}
+        .actions {{
+            display: flex;
+            gap: 16px;
+            margin-top: 24px;
+            justify-content: center;
+        }}
+        .btn {{
+            display: inline-flex;
+            align-items: center;
+            gap: 8px;
+            padding: 12px 24px;
+            border-radius: 8px;
+            font-weight: 500;
+            text-decoration: none;
+            cursor: pointer;
+            border: none;
+            font-size: 1rem;
+        }}
+        .btn-primary {{
+            background: linear-gradient(135deg, #1a5276, #2980b9);
+            color: #fff;
+        }}
+        .btn-secondary {{
+            background: #f0f0f0;
+            color: #333;
+        }}
+        .timestamp {{
+            text-align: center;
+            color: rgba(255,255,255,0.8);
+            font-size: 0.85rem;
+            margin-top: 24px;
+        }}
+        @media print {{
+            body {{ background: #fff; padding: 20px; }}
+            .card {{ box-shadow: none; border: 1px solid #ddd; }}
+            .actions {{ display: none; }}
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <img src="/images/logo.webp" alt="South City Computer" class="logo">
+            <h1>{t_inquiry}</h1>
+            <span class="service-badge">{service_type}</span>
+        </div>
+        <div class="card">
+            <h3 class="section-title">{t_contact}</h3>
+            <div class="info-row">
+                <span class="label">{t_name}</span>
+                <span class="value">{name}</span>
+            </div>
+            <div class="info-row">
+                <span class="label">{t_email}</span>
+                <span class="value"><a href="mailto:{email}">{email}</a></span>
+            </div>
+            <div class="info-row">
+                <span class="label">{t_phone}</span>
+                <span class="value">{phone}</span>
+            </div>
+
+            {answers_section}
+
+            <h3 class="section-title">{t_details}</h3>
+            <div class="message-box">{details}</div>
+
+            <div class="actions">
+                <a href="/view/{id}/pdf" class="btn btn-primary">{t_intake}</a>
+                <button class="btn btn-secondary" id="print">🖨️ {t_print}</button>
+                <a href="/contact-admin" class="btn btn-secondary">← {t_back}</a>
+            </div>
+        </div>
+        <p class="timestamp">{t_submitted}: {timestamp} | ID: {id}</p>
+    </div>
+    <script nonce="{nonce}">
+        document.getElementById('print').addEventListener('click', function() {{ window.print(); }});
+    </script>
+</body>
+</html>"#,
+        service_type = html_escape(&inquiry.service_type),
+        name = html_escape(&inquiry.name),
+        email = html_escape(&inquiry.email),
+        phone = html_escape(&inquiry.phone),
+        details = html_escape(&inquiry.details),
+        answers_section = if !answers_html.is_empty() {
+            format!(r#"<h3 class="section-title">{}</h3>{}"#, t("Form Responses", "Respuestas del formulario"), answers_html)
+        } else {
+            String::new()
+        },
+        timestamp = html_escape(&inquiry.timestamp),
+        id = html_escape(&inquiry.id),
+        nonce = nonce,
+        lang = lang.code(),
+        t_inquiry = t("Service Inquiry", "Solicitud de servicio"),
+        t_contact = t("Contact Information", "Información de contacto"),
+        t_name = t("Name", "Nombre"),
+        t_email = t("Email", "Correo"),
+        t_phone = t("Phone", "Teléfono"),
+        t_details = t("Additional Details", "Detalles adicionales"),
+        t_intake = t("Intake Form PDF", "PDF del formulario de ingreso"),
+        t_print = t("Print", "Imprimir"),
+        t_back = t("Back to Admin", "Volver al panel"),
+        t_submitted = t("Submitted", "Enviado")
+    )
+}
+
+fn generate_view_html(sub: &Submission, lang: lang::Lang) -> String {
+    let t = |en, es| lang.pick(en, es);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #e91e8c 0%, #ffeb3b 50%, #00bcd4 100%);
+            min-height: 100vh;
+            padding: 40px 20px;
+        }}
+        .container {{
+            max-width: 700px;
+            margin: 0 auto;
+            background: #fff;
+            border-radius: 16px;
+            box-shadow: 0 8px 32px rgba(0,0,0,0.2);
+            overflow: hidden;
+        }}
+        .header {{
+            background: #1a1a1a;
+            color: #fff;
+            padding: 32px;
+            text-align: center;
+        }}
+        .header h1 {{
+            font-size: 1.5rem;
+            margin-bottom: 8px;
+        }}
+        .header p {{
+            color: #888;
+            font-size: 0.875rem;
+        }}
+        .content {{
+            padding: 32px;
+        }}
+        .field {{
+            margin-bottom: 24px;
+        }}
+        .field-label {{
+            font-size: 0.75rem;
+            text-transform: uppercase;
+            letter-spacing: 0.5px;
+            color: #888;
+            margin-bottom: 8px;
+        }}
+        .field-value {{
+            font-size: 1.125rem;
+            color: #333;
+            line-height: 1.6;
+        }}
+        .message-box {{
+            background: #f5f5f5;
+            padding: 20px;
+            border-radius: 8px;
+            border-left: 4px solid #e91e8c;
+        }}
+        .actions {{
+            display: flex;
+            gap: 16px;
+            padding: 24px 32px;
+            background: #f9f9f9;
+            border-top: 1px solid #eee;
+        }}
+        .btn {{
+            display: inline-block;
+            padding: 12px 24px;
+            border-radius: 8px;
+            font-weight: 600;
+            text-decoration: none;
+            text-align: center;
+            transition: all 0.3s ease;
+        }}
+        .btn-primary {{
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            color: #fff;
+        }}
+        .btn-primary:hover {{
+            transform: translateY(-2px);
+            box-shadow: 0 4px 12px rgba(233, 30, 140, 0.4);
+        }}
+        .btn-secondary {{
+            background: #eee;
+            color: #333;
+        }}
+        .btn-secondary:hover {{
+            background: #ddd;
+        }}
+        .ref-id {{
+            font-family: monospace;
+            background: #333;
+            color: #00bcd4;
+            padding: 4px 8px;
+            border-radius: 4px;
+            font-size: 0.875rem;
+        }}
+        @media print {{
+            body {{ background: #fff; padding: 0; }}
+            .container {{ box-shadow: none; }}
+            .actions {{ display: none; }}
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>{t_received}</h1>
+            <p>{t_reference}: <span class="ref-id">{}</span></p>
+        </div>
+        <div class="content">
+            <div class="field">
+                <div class="field-label">{t_submitted}</div>
+                <div class="field-value">{}</div>
+            </div>
+            <div class="field">
+                <div class="field-label">{t_name}</div>
+                <div class="field-value">{}</div>
+            </div>
+            <div class="field">
+                <div class="field-label">{t_email}</div>
+                <div class="field-value">{}</div>
+            </div>
+            <div class="field">
+                <div class="field-label">{t_phone}</div>
+                <div class="field-value">{}</div>
+            </div>
+            <div class="field">
+                <div class="field-label">{t_message}</div>
+                <div class="field-value message-box">{}</div>
+            </div>
+        </div>
+        <div class="actions">
+            <a href="/view/{}/pdf" class="btn btn-primary">{t_download}</a>
+            <a href="/" class="btn btn-secondary">{t_back}</a>
+        </div>
+    </div>
+</body>
+</html>"#,
+        html_escape(&sub.id),
+        html_escape(&sub.timestamp),
+        html_escape(&sub.name),
+        html_escape(&sub.email),
+        html_escape(if sub.phone.is_empty() { t("Not provided", "No proporcionado") } else { &sub.phone }),
+        html_escape(&sub.message),
+        html_escape(&sub.id),
+        lang = lang.code(),
+        t_title = t("Your Submission", "Tu envío"),
+        t_received = t("Submission Received", "Envío recibido"),
+        t_reference = t("Reference ID", "ID de referencia"),
+        t_submitted = t("Submitted", "Enviado"),
+        t_name = t("Name", "Nombre"),
+        t_email = t("Email", "Correo"),
+        t_phone = t("Phone", "Teléfono"),
+        t_message = t("Message", "Mensaje"),
+        t_download = t("Download PDF", "Descargar PDF"),
+        t_back = t("Back to Site", "Volver al sitio"),
+    )
+}
+
+fn generate_not_found_html(lang: lang::Lang) -> String {
+    format!(r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title} - South City Computer</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: #1a1a1a;
+            color: #fff;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            text-align: center;
+            padding: 20px;
+        }}
+        h1 {{
+            font-size: 4rem;
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            -webkit-background-clip: text;
+            -webkit-text-fill-color: transparent;
+            background-clip: text;
+            margin-bottom: 16px;
+        }}
+        p {{
+            color: #888;
+            margin-bottom: 24px;
+        }}
+        a {{
+            color: #00bcd4;
+            text-decoration: none;
+        }}
+        a:hover {{
+            text-decoration: underline;
+        }}
+    </style>
+</head>
+<body>
+    <div>
+        <h1>404</h1>
+        <p>{message}</p>
+        <a href="/">{back}</a>
+    </div>
+</body>
+</html>"#,
+        lang = lang.code(),
+        title = lang.pick("Not Found", "No encontrado"),
+        message = lang.pick("Submission not found or link has expired.", "Envío no encontrado o el enlace ha expirado."),
+        back = lang.pick("Back to South City Computer", "Volver a South City Computer")
+    )
+}
+
+async fn download_pdf(path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+
+    let (result, filename) = if let Some(submission) = find_submission_by_id(&id) {
+        (generate_pdf(&submission), format!("submission-{}.pdf", submission.id))
+    } else if let Some(inquiry) = find_service_inquiry_by_id(&id) {
+        (intake::generate_service_inquiry_pdf(&inquiry), format!("intake-{}.pdf", inquiry.id))
+    } else {
+        return HttpResponse::NotFound().body("Submission not found");
+    };
+
+    match result {
+        Ok(pdf_bytes) => {
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+                .body(pdf_bytes)
+        }
+        Err(e) => {
+            log::error!("PDF generation error: {}", e);
+            HttpResponse::InternalServerError().body("Failed to generate PDF")
+        }
+    }
+}
+
+const WHITEPAPER_EN: &str = include_str!("../../WHITEPAPER.md");
+const WHITEPAPER_ES: &str = include_str!("../../WHITEPAPER-es.md");
+
+// Rendered once per language; the Markdown is compiled in, so it can't change
+// while the server is running.
+static WHITEPAPER_PDF_CACHE: [std::sync::OnceLock<web::Bytes>; 2] = [std::sync::OnceLock::new(), std::sync::OnceLock::new()];
+
+async fn download_whitepaper_pdf(path: web::Path<String>) -> HttpResponse {
+    let lang = path.into_inner();
+    let is_spanish = lang == "es";
+
+    let cache = &WHITEPAPER_PDF_CACHE[is_spanish as usize];
+    let pdf = match cache.get() {
+        Some(pdf) => Ok(pdf.clone()),
+        None => web::block(move || generate_whitepaper_pdf(is_spanish).map_err(|e| e.to_string()))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()))
+            .map(|bytes| cache.get_or_init(|| web::Bytes::from(bytes)).clone()),
+    };
+
+    match pdf {
+        Ok(pdf_bytes) => {
+            let filename = if is_spanish {
+                "whitepaper-sub-second-website-es.pdf"
+            } else {
+                "whitepaper-sub-second-website.pdf"
+            };
+            HttpResponse::Ok()
+                .content_type("application/pdf")
+                .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+                .body(pdf_bytes)
+        }
+        Err(e) => {
+            log::error!("Whitepaper PDF generation error: {}", e);
+            HttpResponse::InternalServerError().body("Failed to generate PDF")
+        }
+    }
+}
+
+fn generate_whitepaper_pdf(spanish: bool) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let markdown = if spanish { WHITEPAPER_ES } else { WHITEPAPER_EN };
+    let title = markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .unwrap_or("The Sub-Second Website");
+
+    let mut pdf = pdf_layout::PdfLayout::new(title, pdf_layout::PageStyle {
+        header_title: "SOUTH CITY COMPUTER".to_string(),
+        header_subtitle: if spanish { "Documento Técnico" } else { "Technical White Paper" }.to_string(),
+        footer: "South City Computer | Puerto Morelos, Mexico | southcitycomputer.com".to_string(),
+        page_label: if spanish { "Página" } else { "Page" }.to_string(),
+    })?;
+    markdown_pdf::render(&mut pdf, markdown)?;
+    pdf.finish()
+}
+
+fn generate_pdf(sub: &Submission) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut pdf = pdf_layout::PdfLayout::new("Contact Submission", pdf_layout::PageStyle {
+        header_title: "SOUTH CITY COMPUTER".to_string(),
+        header_subtitle: "Contact Form Submission".to_string(),
+        footer: "South City Computer | Puerto Morelos, Mexico | southcitycomputer.com".to_string(),
+        page_label: "Page".to_string(),
+    })?;
+
+    let (view_url, caption) = scan::view_link(&sub.id);
+    pdf.qr_code(&view_url, &caption)?;
+    pdf.text(&format!("Reference ID: {}", sub.id), 10.0, pdf_layout::Weight::Regular);
+    pdf.space(8.0);
+
+    pdf.field("SUBMITTED", &sub.timestamp);
+    pdf.field("NAME", &sub.name);
+    pdf.field("EMAIL", &sub.email);
+    let phone_text = if sub.phone.is_empty() { "Not provided" } else { &sub.phone };
+    pdf.field("PHONE", phone_text);
+    pdf.field("MESSAGE", &sub.message);
+
+    pdf.finish()
+}
+
+fn check_auth(req: &HttpRequest) -> bool {
+    let auth_header = match req.headers().get("Authorization") {
+        Some(header) => header,
+        None => return false,
+    };
+
+    let auth_str = match auth_header.to_str() {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+
+    if !auth_str.starts_with("Basic ") {
+        return false;
+    }
+
+    let encoded = &auth_str[6..];
+    let decoded = match base64::engine::general_purpose::STANDARD.decode(encoded) {
+        Ok(d) => d,
+        Err(_) => return false,
+    };
+
+    let credentials = match String::from_utf8(decoded) {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+
+    let accounts_content = match fs::read_to_string(config::get().accounts_path()) {
+        Ok(content) => content,
+        Err(_) => return false,
+    };
+
+    for line in accounts_content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if credentials == line {
+            return true;
+        }
+    }
+
+    false
+}
+
+async fn contact_admin(req: HttpRequest) -> HttpResponse {
+    if !check_auth(&req) {
+        return HttpResponse::Unauthorized()
+            .insert_header(("WWW-Authenticate", "Basic realm=\"Contact Admin\""))
+            .body("Unauthorized");
+    }
+
+    let contacts = records::load_contacts();
+    let service_inquiries = records::load_service_inquiries();
+    let honeypot_attempts = records::load_honeypot_attempts();
+
+    let lang = lang::negotiate(&req);
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CONTENT_LANGUAGE, lang.code()))
+        .insert_header((header::VARY, "Accept-Language, Cookie"))
+        .body(generate_admin_html(&contacts, &service_inquiries, &honeypot_attempts, lang, &security_headers::nonce(&req)))
+}
+
+fn generate_admin_html(
+    contacts: &[Submission],
+    service_inquiries: &[ServiceInquiryRecord],
+    honeypot_attempts: &[HoneypotRecord],
+    lang: lang::Lang,
+    nonce: &str,
+) -> String {
+    let t = |en, es| lang.pick(en, es);
+    let contact_rows = if contacts.is_empty() {
+        format!("<tr><td colspan=\"6\" style=\"text-align: center; padding: 40px; color: #888;\">{}</td></tr>", t("No contacts yet", "Aún no hay contactos"))
+    } else {
+        contacts
+            .iter()
+            .rev()
+            .map(|sub| {
+                let id = html_escape(&sub.id);
+                let email = html_escape(&sub.email);
+
+                format!(
+                    "<tr><td><a href=\"/view/{}\">{}</a></td><td>{}</td><td>{}</td><td><a href=\"mailto:{}\">{}</a></td><td>{}</td><td>{}</td></tr>",
+                    id, id, html_escape(&sub.timestamp), html_escape(&sub.name), email, email,
+                    html_escape(&sub.phone), html_escape(&sub.message)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let service_rows = if service_inquiries.is_empty() {
+        format!("<tr><td colspan=\"9\" style=\"text-align: center; padding: 40px; color: #888;\">{}</td></tr>", t("No service inquiries yet", "Aún no hay solicitudes de servicio"))
+    } else {
+        service_inquiries
+            .iter()
+            .rev()
+            .map(|inquiry| {
+                let id = html_escape(&inquiry.id);
+                let email = html_escape(&inquiry.email);
+
+                // Format JSON answers nicely
+                let answers_html = if let Some(obj) = inquiry.answers.as_object() {
+                    obj.iter()
+                        .filter(|(k, _)| *k != "service_type" && *k != "name" && *k != "email" && *k != "phone" && *k != "details")
+                        .map(|(k, v)| {
+                            let value = match v {
+                                serde_json::Value::Array(arr) => arr.iter()
+                                    .filter_map(|x| x.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                                serde_json::Value::String(s) => s.clone(),
+                                _ => v.to_string(),
+                            };
+                            format!("<div class=\"answer-item\"><span class=\"answer-key\">{}</span>: {}</div>",
+                                html_escape(k), html_escape(&value))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("")
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "<tr><td><a href=\"/view/{}\">{}</a><br><a href=\"/contact-admin/quotes/new/{}\">+ {}</a></td><td>{}</td><td><span class=\"service-tag\">{}</span></td><td>{}</td><td><a href=\"mailto:{}\">{}</a></td><td>{}</td><td>{}</td><td class=\"answers-cell\">{}</td></tr>",
+                    id, id, id, t("Quote", "Cotización"), html_escape(&inquiry.timestamp), html_escape(&inquiry.service_type),
+                    html_escape(&inquiry.name), email, email, html_escape(&inquiry.phone),
+                    html_escape(&inquiry.details), answers_html
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    // Collect unique IPs for map (with timestamp for JS)
+    let honeypot_ips: Vec<serde_json::Value> = honeypot_attempts
+        .iter()
+        .filter(|a| !a.ip.is_empty() && a.ip != "unknown" && a.ip != "127.0.0.1")
+        .map(|a| serde_json::json!({ "ip": a.ip, "time": a.timestamp, "source": a.source }))
+        .collect();
+    // Escape '<' so attacker-supplied values can't close the <script> block
+    let honeypot_ips_json = serde_json::Value::Array(honeypot_ips).to_string().replace('<', "\\u003c");
+
+    let honeypot_rows = if honeypot_attempts.is_empty() {
+        format!("<tr><td colspan=\"8\" style=\"text-align: center; padding: 40px; color: #888;\">{}</td></tr>", t("No honeypot attempts yet", "Aún no hay intentos en el honeypot"))
+    } else {
+        honeypot_attempts
+            .iter()
+            .rev()
+            .map(|a| {
+                let ip = html_escape(&a.ip);
+                format!(
+                    "<tr><td>{}</td><td class=\"source-badge\">{}</td><td class=\"honeypot-cred\">{}</td><td class=\"honeypot-cred\">{}</td><td class=\"ip-cell\" data-ip=\"{}\">{}</td><td>{}</td><td>{}</td><td class=\"webgl-cell\">{}</td></tr>",
+                    html_escape(&a.timestamp), html_escape(&a.source), html_escape(&a.username),
+                    html_escape(&a.password), ip, ip, html_escape(&a.screen), html_escape(&a.platform),
+                    html_escape(&a.webgl)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Contact Admin - South City Computer</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: #1a1a1a;
+            color: #fff;
+            min-height: 100vh;
+            padding: 20px;
+        }}
+        .container {{
+            max-width: 1400px;
+            margin: 0 auto;
+        }}
+        h1, h2 {{
+            margin-bottom: 24px;
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            -webkit-background-clip: text;
+            -webkit-text-fill-color: transparent;
+            background-clip: text;
+        }}
+        h2 {{
+            margin-top: 48px;
+        }}
+        .stats {{
+            display: flex;
+            gap: 16px;
+            margin-bottom: 24px;
+            flex-wrap: wrap;
+        }}
+        .stat {{
+            background: #333;
+            padding: 16px 24px;
+            border-radius: 8px;
+        }}
+        .stat-value {{
+            font-size: 2rem;
+            font-weight: bold;
+            color: #00bcd4;
+        }}
+        .stat.magenta .stat-value {{
+            color: #e91e8c;
+        }}
+        .stat-label {{
+            color: #888;
+            font-size: 0.875rem;
+        }}
+        table {{
+            width: 100%;
+            border-collapse: collapse;
+            background: #333;
+            border-radius: 8px;
+            overflow: hidden;
+            margin-bottom: 24px;
+        }}
+        th, td {{
+            padding: 16px;
+            text-align: left;
+            border-bottom: 1px solid #444;
+        }}
+        th {{
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            font-weight: 600;
+            text-transform: uppercase;
+            font-size: 0.75rem;
+            letter-spacing: 0.5px;
+        }}
+        tr:hover {{
+            background: #3a3a3a;
+        }}
+        td:last-child {{
+            max-width: 300px;
+            word-wrap: break-word;
+        }}
+        a {{
+            color: #00bcd4;
+            text-decoration: none;
+        }}
+        a:hover {{
+            text-decoration: underline;
+        }}
+        .back-link {{
+            display: inline-block;
+            margin-bottom: 24px;
+            color: #888;
+        }}
+        .back-link:hover {{
+            color: #00bcd4;
+        }}
+        .service-tag {{
+            background: linear-gradient(135deg, #e91e8c, #00bcd4);
+            padding: 4px 10px;
+            border-radius: 12px;
+            font-size: 0.75rem;
+            font-weight: 600;
+            text-transform: uppercase;
+        }}
+        .answers-cell {{
+            max-width: 350px;
+            font-size: 0.85rem;
+        }}
+        .answer-item {{
+            padding: 4px 0;
+            border-bottom: 1px solid #444;
+        }}
+        .answer-item:last-child {{
+            border-bottom: none;
+        }}
+        .answer-key {{
+            color: #00bcd4;
+            font-weight: 600;
+            text-transform: capitalize;
+        }}
+        .honeypot-cred {{
+            font-family: monospace;
+            background: #2a2a2a;
+            padding: 4px 8px;
+            border-radius: 4px;
+            color: #ff6b6b;
+        }}
+        .source-badge {{
+            font-size: 0.7rem;
+            padding: 4px 8px;
+            border-radius: 4px;
+            background: #8b5cf6;
+            color: #fff;
+            text-transform: uppercase;
+            font-weight: 600;
+        }}
+        .ip-cell {{
+            font-family: monospace;
+            color: #f59e0b;
+        }}
+        .webgl-cell {{
+            max-width: 200px;
+            font-size: 0.7rem;
+            color: #888;
+            white-space: nowrap;
+            overflow: hidden;
+            text-overflow: ellipsis;
+        }}
+        .ua-cell {{
+            max-width: 300px;
+            font-size: 0.75rem;
+            color: #888;
+            word-break: break-all;
+        }}
+        .stat.red .stat-value {{
+            color: #ff6b6b;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <a href="/" class="back-link">&larr; {t_back}</a>
+        <a href="/contact-admin/quotes" class="back-link" style="float: right;">{t_quotes} &rarr;</a>
+        <a href="/contact-admin/scan" class="back-link" style="float: right; margin-right: 20px;">{t_scan}</a>
+        <a href="/contact-admin/analytics" class="back-link" style="float: right; margin-right: 20px;">{t_analytics}</a>
+        <h1>{t_admin}</h1>
+        <div class="stats">
+            <div class="stat">
+                <div class="stat-value">{}</div>
+                <div class="stat-label">{t_contact_form}</div>
+            </div>
+            <div class="stat magenta">
+                <div class="stat-value">{}</div>
+                <div class="stat-label">{t_inquiries}</div>
+            </div>
+            <div class="stat red">
+                <div class="stat-value">{}</div>
+                <div class="stat-label">{t_catches}</div>
+            </div>
+            <div class="stat">
+                <div class="stat-value">{}</div>
+                <div class="stat-label">{t_legitimate}</div>
+            </div>
+        </div>
+
+        <h2>{t_submissions}</h2>
+        <table>
+            <thead>
+                <tr>
+                    <th>ID</th>
+                    <th>{t_timestamp}</th>
+                    <th>{t_name}</th>
+                    <th>{t_email}</th>
+                    <th>{t_phone}</th>
+                    <th>{t_message}</th>
+                </tr>
+            </thead>
+            <tbody>
+                {}
+            </tbody>
+        </table>
+
+        <h2>{t_inquiries}</h2>
+        <table>
+            <thead>
+                <tr>
+                    <th>ID</th>
+                    <th>{t_timestamp}</th>
+                    <th>{t_service}</th>
+                    <th>{t_name}</th>
+                    <th>{t_email}</th>
+                    <th>{t_phone}</th>
+                    <th>{t_details}</th>
+                    <th>{t_answers}</th>
+                </tr>
+            </thead>
+            <tbody>
+                {}
+            </tbody>
+        </table>
+
+        <h2>{t_map}</h2>
+        <div id="attack-map" style="height: 400px; border-radius: 12px; margin-bottom: 30px; background: #2a2a2a;"></div>
+
+        <h2>{t_catches}</h2>
+        <table>
+            <thead>
+                <tr>
+                    <th>{t_timestamp}</th>
+                    <th>{t_source}</th>
+                    <th>{t_username}</th>
+                    <th>{t_password}</th>
+                    <th>IP</th>
+                    <th>{t_screen}</th>
+                    <th>{t_platform}</th>
+                    <th>GPU</th>
+                </tr>
+            </thead>
+            <tbody>
+                {}
+            </tbody>
+        </table>
+    </div>
+
+    <link rel="stylesheet" href="https://unpkg.com/leaflet@1.9.4/dist/leaflet.css" />
+    <script nonce="{nonce}" src="https://unpkg.com/leaflet@1.9.4/dist/leaflet.js"></script>
+    <script nonce="{nonce}">
+    (function() {{
+        var attempts = {};
+        if (attempts.length === 0) {{
+            document.getElementById('attack-map').innerHTML = '<div style="display:flex;align-items:center;justify-content:center;height:100%;color:#888;">No attack data with valid IPs yet</div>';
+            return;
+        }}
+
+        var map = L.map('attack-map').setView([30, 0], 2);
+        L.tileLayer('https://{{s}}.basemaps.cartocdn.com/dark_all/{{z}}/{{x}}/{{y}}{{r}}.png', {{
+            attribution: '&copy; CartoDB',
+            maxZoom: 19
+        }}).addTo(map);
+
+        var processed = {{}};
+        attempts.forEach(function(a) {{
+            if (processed[a.ip]) return;
+            processed[a.ip] = true;
+
+            fetch('https://ipapi.co/' + a.ip + '/json/')
+                .then(function(r) {{ return r.json(); }})
+                .then(function(geo) {{
+                    if (geo.latitude && geo.longitude) {{
+                        var marker = L.circleMarker([geo.latitude, geo.longitude], {{
+                            radius: 8,
+                            fillColor: '#ff4444',
+                            color: '#ff0000',
+                            weight: 2,
+                            opacity: 1,
+                            fillOpacity: 0.7
+                        }}).addTo(map);
+
+                        marker.bindPopup(
+                            '<strong>' + a.ip + '</strong><br>' +
+                            'Source: ' + a.source + '<br>' +
+                            'Time: ' + a.time + '<br>' +
+                            'Location: ' + (geo.city || 'Unknown') + ', ' + (geo.country_name || 'Unknown')
+                        );
+                    }}
+                }})
+                .catch(function() {{}});
+        }});
+    }})();
+    </script>
+</body>
+</html>"#,
+        contacts.len(),
+        service_inquiries.len(),
+        honeypot_attempts.len(),
+        contacts.len() + service_inquiries.len(),
+        contact_rows,
+        service_rows,
+        honeypot_rows,
+        honeypot_ips_json,
+        nonce = nonce,
+        lang = lang.code(),
+        t_back = t("Back to site", "Volver al sitio"),
+        t_quotes = t("Quotes &amp; Invoices", "Cotizaciones y facturas"),
+        t_scan = t("Scan Document", "Escanear documento"),
+        t_analytics = t("Analytics", "Analíticas"),
+        t_admin = t("Contact Admin", "Administración de contactos"),
+        t_contact_form = t("Contact Form", "Formulario de contacto"),
+        t_inquiries = t("Service Inquiries", "Solicitudes de servicio"),
+        t_catches = t("Honeypot Catches", "Capturas del honeypot"),
+        t_legitimate = t("Total Legitimate", "Total legítimos"),
+        t_submissions = t("Contact Form Submissions", "Mensajes del formulario de contacto"),
+        t_map = t("Honeypot Attack Map", "Mapa de ataques al honeypot"),
+        t_timestamp = t("Timestamp", "Fecha"),
+        t_name = t("Name", "Nombre"),
+        t_email = t("Email", "Correo"),
+        t_phone = t("Phone", "Teléfono"),
+        t_message = t("Message", "Mensaje"),
+        t_service = t("Service", "Servicio"),
+        t_details = t("Details", "Detalles"),
+        t_answers = t("Answers", "Respuestas"),
+        t_source = t("Source", "Origen"),
+        t_username = t("Username", "Usuario"),
+        t_password = t("Password", "Contraseña"),
+        t_screen = t("Screen", "Pantalla"),
+        t_platform = t("Platform", "Plataforma")
+    )
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+async fn health_check() -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        message: "Server is running".to_string(),
+    })
+}
+
+async fn serve_embedded(req: HttpRequest) -> HttpResponse {
+    let path = req.path().trim_start_matches('/').to_string();
+    serve_file(&req, &path)
+}
+
+async fn serve_index(req: HttpRequest) -> HttpResponse {
+    serve_file(&req, "")
+}
+
+/// Cache-Control for fingerprinted URLs, whose content never changes
+const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+
+/// Cache-Control for plain asset paths. Pages reference images, CSS and JS by
+/// fingerprinted URL, so these only serve old links and must pick up deploys.
+fn get_cache_header(path: &str) -> &'static str {
+    if path.ends_with(".css") || path.ends_with(".js") || path.ends_with(".html") {
+        "public, max-age=3600"
+    } else {
+        "public, max-age=86400"
+    }
+}
+
+/// Asset for a request path: the exact file, then a clean URL
+/// (`about` -> `about.html`), then a directory index
+fn resolve_asset(source: &dyn assets::AssetSource, path: &str) -> Option<(String, rust_embed::EmbeddedFile)> {
+    if let Some(content) = source.get(path) {
+        return Some((path.to_string(), content));
+    }
+
+    // If no extension, try adding .html (clean URLs)
+    if !path.contains('.') && !path.is_empty() {
+        let html_path = format!("{}.html", path);
+        if let Some(content) = source.get(&html_path) {
+            return Some((html_path, content));
+        }
+    }
+
+    // Try index.html for directories
+    if path.ends_with('/') || !path.contains('.') {
+        let index_path = if path.is_empty() {
+            "index.html".to_string()
+        } else {
+            format!("{}/index.html", path.trim_end_matches('/'))
+        };
+        if let Some(content) = source.get(&index_path) {
+            return Some((index_path, content));
+        }
+    }
+
+    None
+}
+
+fn serve_file(req: &HttpRequest, path: &str) -> HttpResponse {
+    let path = path.trim_start_matches('/');
+    let source = assets::source();
+
+    // Fingerprinted URLs (css/style.min.<hash>.css) name one exact version
+    let original = asset_http::unfingerprinted(path);
+    let resolved = match original {
+        Some(original) => source.get(original).map(|content| (original.to_string(), content)),
+        None => resolve_asset(source, path),
+    };
+    let Some((mut asset_path, mut content)) = resolved else {
+        let lang = lang::negotiate(req);
+        return HttpResponse::NotFound()
+            .content_type("text/html; charset=utf-8")
+            .insert_header((header::CONTENT_LANGUAGE, lang.code()))
+            .body(generate_not_found_html(lang));
+    };
+
+    // Pages with a Spanish twin: clean URLs serve the visitor's language,
+    // explicit .html paths are served as asked
+    let mut twin = lang::twin_path(&asset_path).filter(|twin| source.get(twin).is_some());
+    let negotiated = twin.is_some() && asset_path != path && lang::page_lang(&asset_path) == lang::Lang::En;
+    if negotiated && lang::negotiate(req) == lang::Lang::Es {
+        if let Some((spanish, file)) = twin.clone().and_then(|path| source.get(&path).map(|file| (path, file))) {
+            twin = Some(std::mem::replace(&mut asset_path, spanish));
+            content = file;
+        }
+    }
+
+    let built = asset_http::built(&asset_path, &content);
+    let encoded = built.and_then(|b| asset_http::negotiate(req, b));
+    let sha256 = built.map(|b| *b.sha256()).unwrap_or_else(|| content.metadata.sha256_hash());
+    let etag = asset_http::etag(&sha256, encoded.map(|(coding, _)| coding));
+    let last_modified = source.last_modified(&content);
+    let not_modified = asset_http::not_modified(req, &etag, last_modified);
+    analytics::record_view(req, &asset_path);
+
+    let mut response = if not_modified { HttpResponse::NotModified() } else { HttpResponse::Ok() };
+    response
+        .insert_header((
+            header::CACHE_CONTROL,
+            source.cache_control().unwrap_or(if original.is_some() && asset_http::fingerprint_current(path, built, &sha256) {
+                IMMUTABLE_CACHE
+            } else {
+                get_cache_header(&asset_path)
+            }),
+        ))
+        .insert_header(header::ETag(etag.clone()))
+        .insert_header(header::LastModified(last_modified.into()));
+    let mut vary = Vec::new();
+    if built.is_some_and(|b| b.has_variants()) {
+        vary.push("Accept-Encoding");
+    }
+    if negotiated {
+        vary.push("Accept-Language, Cookie");
+    }
+    if !vary.is_empty() {
+        response.insert_header((header::VARY, vary.join(", ")));
+    }
+    if let Some(twin) = &twin {
+        response
+            .insert_header((header::CONTENT_LANGUAGE, lang::page_lang(&asset_path).code()))
+            .insert_header((header::LINK, lang::alternates(&asset_path, twin)));
+    }
+    if not_modified {
+        return response.finish();
+    }
+
+    let content_type = if asset_path.ends_with(".html") {
+        "text/html; charset=utf-8".to_string()
+    } else {
+        from_path(&asset_path).first_or_octet_stream().to_string()
+    };
+    response.content_type(content_type.as_str());
+    let body = match encoded {
+        Some((coding, body)) => {
+            response.insert_header((header::CONTENT_ENCODING, coding));
+            web::Bytes::from_static(body)
+        }
+        None => {
+            // Keep middleware::Compress off embedded assets, so byte ranges
+            // always refer to the bytes the ETag describes
+            if req.headers().contains_key(header::ACCEPT_ENCODING) {
+                response.insert_header((header::CONTENT_ENCODING, "identity"));
+            }
+            match built.and_then(|b| b.body()) {
+                Some(rewritten) => web::Bytes::from_static(rewritten),
+                None => asset_http::static_bytes(content.data),
+            }
+        }
+    };
+    let response = asset_http::ranged_body(req, response, body, &etag, last_modified, &content_type);
+    if let BodySize::Sized(bytes) = response.body().size() {
+        metrics::asset_served(&asset_path, bytes);
+    }
+    response
+}
+
+/// Print every asset the server will serve, with its size
+pub fn list_assets() {
+    let source = assets::source();
+    println!("\nAssets:");
+    let mut paths = source.paths();
+    paths.sort();
+    for file in paths {
+        if let Some(asset) = source.get(&file) {
+            println!("  {} ({} bytes)", file, asset.data.len());
+        }
+    }
+    println!();
+}
+
+/// The whole site: middleware, the form API, record and admin pages, and the
+/// static files from assets::source()
+pub fn app() -> App<
+    impl ServiceFactory<ServiceRequest, Config = (), Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error, InitError = ()>,
+> {
+    App::new()
+        .wrap(middleware::from_fn(security_headers::security_headers))
+        .wrap(middleware::from_fn(cors::cors))
+        .wrap(middleware::Compress::default())
+        .wrap(middleware::from_fn(tls::redirect))
+        .wrap(middleware::from_fn(metrics::track))
+        .wrap(middleware::from_fn(logging::requests))
+        .route("/api/contact", web::post().to(handle_contact))
+        .route("/api/service-inquiry", web::post().to(handle_service_inquiry))
+        .route("/api/honeypot", web::post().to(handle_honeypot))
+        .route("/view/{id}", web::get().to(view_submission))
+        .route("/view/{id}/pdf", web::get().to(download_pdf))
+        .route("/whitepaper/pdf/{lang}", web::get().to(download_whitepaper_pdf))
+        .route("/contact-admin", web::get().to(contact_admin))
+        .route("/health", web::get().to(health_check))
+        .configure(health::configure_health)
+        .configure(metrics::configure_metrics)
+        .configure(billing::configure_billing)
+        .configure(scan::configure_scan)
+        .configure(seo::configure_seo)
+        .configure(decoys::configure_decoys)
+        .configure(overlay::configure_overlay)
+        .configure(analytics::configure_analytics)
+        .route("/", web::get().to(serve_index))
+        .default_service(web::get().to(serve_embedded))
+}
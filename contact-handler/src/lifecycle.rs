@@ -0,0 +1,209 @@
+// Startup and shutdown of scc-server under systemd
+// SIGTERM (or Ctrl-C) stops accepting connections and lets the requests in
+// flight finish, for up to server.shutdown_timeout seconds; a second signal
+// stops at once. Once the workers have stopped, anything still held in memory
+// (the page view counts) is written out. /health/ready answers 503 while
+// draining.
+//
+// With Type=notify the service manager is told READY=1 once the listeners are
+// up and STOPPING=1 when draining starts. With a .socket unit the listening
+// sockets are passed in (LISTEN_FDS) instead of bound here, so the port stays
+// open across restarts and connections made meanwhile wait in the kernel's
+// backlog rather than being refused. Sockets named "https"
+// (FileDescriptorName=https) serve TLS, the rest plain HTTP. SIGHUP reloads
+// the TLS certificate (see tls.rs).
+//
+// Configuration ([server] in config.rs):
+//   shutdown_timeout - Seconds to wait for in-flight requests (SHUTDOWN_TIMEOUT)
+
+use actix_web::dev::{Server, ServerHandle};
+use std::io;
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::analytics;
+
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+/// Whether a shutdown has started
+pub fn stopping() -> bool {
+    STOPPING.load(Ordering::Relaxed)
+}
+
+/// Send `state` ("READY=1", "STOPPING=1", ...) to the service manager, if
+/// it's listening ($NOTIFY_SOCKET)
+#[cfg(unix)]
+pub fn notify(state: &str) {
+    if let Some(path) = std::env::var_os("NOTIFY_SOCKET") {
+        notify_socket(&path, state);
+    }
+}
+
+/// notify() to the socket at `path` ("@name" for an abstract one)
+#[cfg(unix)]
+fn notify_socket(path: &std::ffi::OsStr, state: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let sent = UnixDatagram::unbound().and_then(|socket| {
+        use std::os::unix::ffi::OsStrExt;
+        match path.as_bytes().strip_prefix(b"@") {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &addr)
+            }
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "abstract sockets are Linux-only")),
+            None => socket.send_to(state.as_bytes(), path),
+        }
+    });
+    if let Err(e) = sent {
+        log::warn!("Could not notify the service manager ({}): {}", state.replace('\n', " "), e);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) {}
+
+/// Listening sockets passed in by systemd socket activation (LISTEN_FDS,
+/// starting at fd 3) with their names (LISTEN_FDNAMES), if any were passed
+/// to this process
+#[cfg(unix)]
+pub fn listeners() -> Vec<(String, TcpListener)> {
+    let [pid, fds, names] = ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"].map(|name| std::env::var(name).ok());
+    // Not for any child process
+    for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(name);
+    }
+    listeners_from(pid.as_deref(), fds.as_deref(), names.as_deref())
+}
+
+/// listeners() for the given LISTEN_PID, LISTEN_FDS and LISTEN_FDNAMES
+#[cfg(unix)]
+fn listeners_from(pid: Option<&str>, fds: Option<&str>, names: Option<&str>) -> Vec<(String, TcpListener)> {
+    use std::os::unix::io::FromRawFd;
+
+    const FIRST_FD: i32 = 3;
+    let for_us = pid.and_then(|pid| pid.parse::<u32>().ok()) == Some(std::process::id());
+    let count = fds.and_then(|n| n.parse::<i32>().ok()).unwrap_or(0);
+    let names: Vec<String> = names.unwrap_or_default().split(':').map(str::to_string).collect();
+    if !for_us {
+        return Vec::new();
+    }
+    (FIRST_FD..FIRST_FD + count.max(0))
+        .filter_map(|fd| {
+            // SAFETY: systemd hands these descriptors to this process and
+            // nothing else in it owns them; close-on-exec keeps them here
+            let listener = unsafe {
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                TcpListener::from_raw_fd(fd)
+            };
+            let name = names.get((fd - FIRST_FD) as usize).filter(|name| !name.is_empty()).cloned().unwrap_or_else(|| "unknown".to_string());
+            match listener.local_addr() {
+                Ok(_) => Some((name, listener)),
+                Err(e) => {
+                    log::warn!("Ignoring passed-in socket {}: not a TCP listener ({})", fd, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+pub fn listeners() -> Vec<(String, TcpListener)> {
+    Vec::new()
+}
+
+/// Write out everything held in memory that should outlive the process
+pub fn flush() {
+    analytics::flush();
+    log::logger().flush();
+}
+
+fn drain(server: &ServerHandle, signal: &str) {
+    log::info!(
+        "{}: finishing in-flight requests (up to {}s), then shutting down",
+        signal,
+        crate::config::get().server.shutdown_timeout
+    );
+    STOPPING.store(true, Ordering::Relaxed);
+    notify("STOPPING=1\nSTATUS=Finishing in-flight requests");
+    actix_web::rt::spawn(server.stop(true));
+}
+
+#[cfg(unix)]
+async fn watch_signals(server: ServerHandle) -> io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut term = signal(SignalKind::terminate())?;
+    let mut int = signal(SignalKind::interrupt())?;
+    let mut hup = signal(SignalKind::hangup())?;
+    loop {
+        let name = tokio::select! {
+            _ = term.recv() => "SIGTERM",
+            _ = int.recv() => "SIGINT",
+            _ = hup.recv() => {
+                crate::tls::reload();
+                continue;
+            }
+        };
+        if stopping() {
+            log::warn!("{} again: stopping without waiting for requests", name);
+            server.stop(false).await;
+            return Ok(());
+        }
+        drain(&server, name);
+    }
+}
+
+#[cfg(not(unix))]
+async fn watch_signals(server: ServerHandle) -> io::Result<()> {
+    loop {
+        tokio::signal::ctrl_c().await?;
+        if stopping() {
+            server.stop(false).await;
+            return Ok(());
+        }
+        drain(&server, "Ctrl-C");
+    }
+}
+
+/// Run `server` (built with signals disabled) until a shutdown signal has
+/// drained it, then flush
+pub async fn serve(server: Server) -> io::Result<()> {
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        if let Err(e) = watch_signals(handle).await {
+            log::error!("Could not watch for shutdown signals: {}", e);
+        }
+    });
+    notify("READY=1\nSTATUS=Serving requests");
+    let result = server.await;
+    flush();
+    log::info!("Shut down");
+    result
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixDatagram;
+
+    #[test]
+    fn test_notify_and_listeners() {
+        let path = std::env::temp_dir().join(format!("scc-notify-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let manager = UnixDatagram::bind(&path).unwrap();
+        notify_socket(path.as_os_str(), "READY=1");
+        let mut buf = [0; 64];
+        let len = manager.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        std::fs::remove_file(&path).unwrap();
+
+        // Sockets meant for another process are left alone
+        assert!(listeners_from(Some("1"), Some("1"), None).is_empty());
+        assert!(listeners_from(None, Some("1"), None).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+// Live reload for scc-dev
+// A watcher thread polls server.static_dir for files that appeared, changed or
+// went away and announces them over a server-sent event stream
+// (/__dev/events). Every HTML page scc-dev serves gets a small client script
+// (/__dev/reload.js) that listens to it: stylesheet edits are swapped in
+// place, anything else reloads the page. When scc-dev itself restarts the
+// stream drops, and the page reloads once it can reconnect.
+//
+// Configuration ([dev] in config.rs):
+//   live_reload       - false to serve pages untouched (LIVE_RELOAD=off)
+//   watch_interval_ms - How often static_dir is checked. Defaults to 300.
+
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{error, web, Error, HttpResponse};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
+
+use crate::assets::{AssetSource, DiskAssets};
+use crate::security_headers;
+
+const EVENTS_PATH: &str = "/__dev/events";
+const CLIENT_PATH: &str = "/__dev/reload.js";
+
+/// Comment line sent on idle streams so proxies don't time them out
+const KEEPALIVE: Duration = Duration::from_secs(20);
+
+const CLIENT_JS: &str = r#"(function () {
+  var source = new EventSource('/__dev/events');
+  var lost = false;
+  source.onerror = function () { lost = true; };
+  source.onopen = function () { if (lost) location.reload(); };
+  source.addEventListener('reload', function () { location.reload(); });
+  source.addEventListener('css', function (event) {
+    var changed = JSON.parse(event.data);
+    var swapped = false;
+    document.querySelectorAll('link[rel="stylesheet"]').forEach(function (link) {
+      var url = new URL(link.href, location.href);
+      if (url.origin !== location.origin) return;
+      // css/style.min.<fingerprint>.css is the same file as css/style.min.css
+      var path = url.pathname.slice(1).replace(/\.[0-9a-f]{10}\.css$/, '.css');
+      if (changed.indexOf(path) < 0) return;
+      var fresh = link.cloneNode();
+      fresh.href = '/' + path + '?v=' + Date.now();
+      fresh.onload = function () { link.remove(); };
+      link.after(fresh);
+      swapped = true;
+    });
+    if (!swapped) location.reload();
+  });
+})();
+"#;
+
+/// What a change in static_dir means for open pages
+#[derive(Clone, Debug, PartialEq)]
+enum Event {
+    /// Only these stylesheets changed
+    Css(Vec<String>),
+    Reload,
+}
+
+impl Event {
+    fn for_changes(paths: &[String]) -> Event {
+        if paths.iter().all(|path| path.ends_with(".css")) {
+            Event::Css(paths.to_vec())
+        } else {
+            Event::Reload
+        }
+    }
+
+    fn to_sse(&self) -> String {
+        match self {
+            Event::Css(paths) => format!("event: css\ndata: {}\n\n", serde_json::to_string(paths).unwrap_or_default()),
+            Event::Reload => "event: reload\ndata: \n\n".to_string(),
+        }
+    }
+}
+
+fn events() -> &'static broadcast::Sender<Event> {
+    static EVENTS: OnceLock<broadcast::Sender<Event>> = OnceLock::new();
+    EVENTS.get_or_init(|| broadcast::channel(16).0)
+}
+
+/// Modification time of every file the dev server serves
+fn snapshot(source: &DiskAssets) -> HashMap<String, SystemTime> {
+    source
+        .paths()
+        .into_iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(source.root().join(&path)).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// Paths added, changed or removed between two snapshots, sorted
+fn changed(before: &HashMap<String, SystemTime>, after: &HashMap<String, SystemTime>) -> Vec<String> {
+    let mut paths: Vec<String> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .chain(before.keys().filter(|path| !after.contains_key(*path)).cloned())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Watch `root` in a background thread, announcing changes to connected pages
+pub fn watch(root: &Path, interval: Duration) {
+    let source = DiskAssets::new(root);
+    let root: PathBuf = root.to_path_buf();
+    std::thread::spawn(move || {
+        let mut files = snapshot(&source);
+        loop {
+            std::thread::sleep(interval);
+            let now = snapshot(&source);
+            let paths = changed(&files, &now);
+            files = now;
+            if paths.is_empty() {
+                continue;
+            }
+            println!("[DEV] Changed in {}: {}", root.display(), paths.join(", "));
+            // No receivers just means no page is open
+            let _ = events().send(Event::for_changes(&paths));
+        }
+    });
+}
+
+async fn event_stream() -> HttpResponse {
+    let receiver = events().subscribe();
+    let hello = futures_util::stream::once(async { Ok::<_, Error>(web::Bytes::from_static(b"retry: 1000\n\n")) });
+    let updates = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        let chunk = match tokio::time::timeout(KEEPALIVE, receiver.recv()).await {
+            Ok(Ok(event)) => event.to_sse(),
+            // Missed some changes: reloading covers all of them
+            Ok(Err(broadcast::error::RecvError::Lagged(_))) => Event::Reload.to_sse(),
+            Ok(Err(broadcast::error::RecvError::Closed)) => return None,
+            Err(_) => ": keep-alive\n\n".to_string(),
+        };
+        Some((Ok::<_, Error>(web::Bytes::from(chunk)), receiver))
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(futures_util::StreamExt::chain(hello, updates))
+}
+
+async fn client_js() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/javascript; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .body(CLIENT_JS)
+}
+
+/// Register the event stream and the client script
+pub fn configure_live_reload(cfg: &mut web::ServiceConfig) {
+    cfg.route(EVENTS_PATH, web::get().to(event_stream)).route(CLIENT_PATH, web::get().to(client_js));
+}
+
+/// `html` with the client script added at the end of the body
+fn with_client(html: &str, nonce: &str) -> String {
+    let nonce = if nonce.is_empty() { String::new() } else { format!(" nonce=\"{}\"", nonce) };
+    let tag = format!("<script src=\"{}\"{}></script>", CLIENT_PATH, nonce);
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(end) => format!("{}{}{}", &html[..end], tag, &html[end..]),
+        None => format!("{}{}", html, tag),
+    }
+}
+
+/// Middleware adding the client script to HTML pages. Wrap it outside the
+/// app: it also turns off compression, so pages can be edited and the event
+/// stream isn't held back by the encoder.
+pub async fn inject_client(mut req: ServiceRequest, next: Next<impl MessageBody + 'static>) -> Result<ServiceResponse<BoxBody>, Error> {
+    req.headers_mut().remove(header::ACCEPT_ENCODING);
+    let res = next.call(req).await?;
+    let is_html = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    if res.status() != StatusCode::OK || !is_html {
+        return Ok(res.map_into_boxed_body());
+    }
+
+    let nonce = security_headers::nonce(res.request());
+    let (req, res) = res.into_parts();
+    let (res, page) = res.into_parts();
+    let page = body::to_bytes(page).await.map_err(|e| error::ErrorInternalServerError(e.into()))?;
+    let page = with_client(&String::from_utf8_lossy(&page), &nonce);
+    Ok(ServiceResponse::new(req, res.set_body(page).map_into_boxed_body()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{middleware::from_fn, App};
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let before = HashMap::from([("index.html".to_string(), at(1)), ("css/style.min.css".to_string(), at(1)), ("old.html".to_string(), at(1))]);
+        let after = HashMap::from([("index.html".to_string(), at(1)), ("css/style.min.css".to_string(), at(2)), ("new.html".to_string(), at(2))]);
+        assert_eq!(changed(&before, &after), vec!["css/style.min.css", "new.html", "old.html"]);
+        assert!(changed(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_event_for_changes() {
+        let css = vec!["css/style.min.css".to_string()];
+        assert_eq!(Event::for_changes(&css), Event::Css(css.clone()));
+        assert_eq!(Event::for_changes(&["css/style.min.css".to_string(), "index.html".to_string()]), Event::Reload);
+        assert_eq!(Event::Css(css).to_sse(), "event: css\ndata: [\"css/style.min.css\"]\n\n");
+    }
+
+    #[test]
+    fn test_with_client() {
+        assert_eq!(with_client("<html><BODY>x</BODY></html>", ""), "<html><BODY>x<script src=\"/__dev/reload.js\"></script></BODY></html>");
+        assert_eq!(with_client("x", "abc"), "x<script src=\"/__dev/reload.js\" nonce=\"abc\"></script>");
+    }
+
+    #[actix_web::test]
+    async fn test_inject_only_into_html() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(inject_client))
+                .route("/page", web::get().to(|| async { HttpResponse::Ok().content_type("text/html; charset=utf-8").body("<body></body>") }))
+                .route("/data", web::get().to(|| async { HttpResponse::Ok().content_type("application/json").body("{}") })),
+        )
+        .await;
+        let page = call_service(&app, TestRequest::get().uri("/page").insert_header((header::ACCEPT_ENCODING, "br")).to_request()).await;
+        assert_eq!(read_body(page).await, "<body><script src=\"/__dev/reload.js\"></script></body>");
+        let data = call_service(&app, TestRequest::get().uri("/data").to_request()).await;
+        assert_eq!(read_body(data).await, "{}");
+    }
+}
@@ -0,0 +1,346 @@
+// Log output
+// A small `log` backend writing one line per record to stderr, as text or as
+// JSON (log.format), with levels per module (log.modules) on top of
+// log.level.
+//
+// Every request gets an ID: the client's X-Request-Id when it sends a sane
+// one, else a fresh one. It is echoed in the response's X-Request-Id and
+// attached to every line logged while the request is handled, including the
+// access log line written when the response is ready.
+//
+// Lines are redacted before they are written (log.redact): email addresses,
+// phone numbers and IP addresses found in messages are masked, and callers
+// pass anything secret through `password()`. The CSV stores keep the full
+// data; only the logs are masked.
+//
+// Configuration ([log] in config.rs):
+//   level      - off, error, warn, info, debug, trace (LOG_LEVEL)
+//   modules    - Per-module levels (LOG_MODULES="scc_server::decoys=warn,...")
+//   format     - text or json (LOG_FORMAT)
+//   redact     - Any of email, phone, password, ip; all by default (LOG_REDACT)
+//   access_log - One line per request (ACCESS_LOG)
+
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use chrono::{Local, SecondsFormat};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::config::{LogConfig, LogFormat};
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Target the access log is written under
+const ACCESS_TARGET: &str = "scc_server::access";
+
+/// Longest client-supplied request ID that is kept
+const MAX_REQUEST_ID: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// What gets masked in log lines
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Redaction {
+    email: bool,
+    phone: bool,
+    password: bool,
+    ip: bool,
+}
+
+impl Redaction {
+    fn from_config(settings: &LogConfig) -> Self {
+        Redaction {
+            email: settings.redacts("email"),
+            phone: settings.redacts("phone"),
+            password: settings.redacts("password"),
+            ip: settings.redacts("ip"),
+        }
+    }
+
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let patterns = patterns();
+        let mut text = Cow::Borrowed(text);
+        if self.email {
+            text = replaced(text, &patterns.email, |_| "[email]".into());
+        }
+        if self.ip {
+            // Only what really parses, so times ("14:03:22") and versions survive
+            text = replaced(text, &patterns.ipv4, |caps| if caps[0].parse::<Ipv4Addr>().is_ok() { "[ip]".into() } else { caps[0].to_string() });
+            text = replaced(text, &patterns.ipv6, |caps| {
+                if caps[2].parse::<Ipv6Addr>().is_ok() {
+                    format!("{}[ip]", &caps[1])
+                } else {
+                    caps[0].to_string()
+                }
+            });
+        }
+        if self.phone {
+            text = replaced(text, &patterns.phone, |_| "[phone]".into());
+        }
+        text
+    }
+}
+
+struct Patterns {
+    email: Regex,
+    phone: Regex,
+    ipv4: Regex,
+    /// A run of hex digits and colons not glued to a word ("scc_server::x")
+    ipv6: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        email: Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(),
+        phone: Regex::new(r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{3}\)|\b\d{3})[\s.-]?\d{3}[\s.-]?\d{4}\b").unwrap(),
+        ipv4: Regex::new(r"\b\d{1,3}(?:\.\d{1,3}){3}\b").unwrap(),
+        ipv6: Regex::new(r"(^|[^\w.:])([0-9A-Fa-f]*:[0-9A-Fa-f:.]*[0-9A-Fa-f])").unwrap(),
+    })
+}
+
+fn replaced<'a>(text: Cow<'a, str>, pattern: &Regex, replacement: impl Fn(&Captures) -> String) -> Cow<'a, str> {
+    if !pattern.is_match(&text) {
+        return text;
+    }
+    Cow::Owned(pattern.replace_all(&text, |caps: &Captures| replacement(caps)).into_owned())
+}
+
+struct Logger {
+    format: LogFormat,
+    level: LevelFilter,
+    /// Longest module first, so the most specific one wins
+    modules: Vec<(String, LevelFilter)>,
+    redaction: Redaction,
+}
+
+impl Logger {
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| target == module || target.strip_prefix(module.as_str()).is_some_and(|rest| rest.starts_with("::")))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+
+    /// One output line; `fields` are extra JSON members (already redacted)
+    fn line(&self, level: Level, target: &str, message: &str, fields: &[(&str, serde_json::Value)]) -> String {
+        let request_id = current_request_id();
+        let message = self.redaction.apply(message);
+        match self.format {
+            LogFormat::Text => {
+                let id = request_id.map(|id| format!("[{}] ", id)).unwrap_or_default();
+                format!("{} {:<5} {}: {}{}", Local::now().format("%Y-%m-%d %H:%M:%S"), level, target, id, message)
+            }
+            LogFormat::Json => {
+                let mut line = serde_json::Map::new();
+                line.insert("ts".into(), Local::now().to_rfc3339_opts(SecondsFormat::Millis, false).into());
+                line.insert("level".into(), level.as_str().into());
+                line.insert("target".into(), target.into());
+                if let Some(id) = request_id {
+                    line.insert("request_id".into(), id.into());
+                }
+                line.insert("msg".into(), message.into_owned().into());
+                for (name, value) in fields {
+                    line.insert(name.to_string(), value.clone());
+                }
+                serde_json::Value::Object(line).to_string()
+            }
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", self.line(record.level(), record.target(), &record.args().to_string(), &[]));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Install the stderr logger with the [log] settings; later calls are ignored
+pub fn init(settings: &LogConfig) {
+    let mut modules = settings.module_filters();
+    modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+    let logger = LOGGER.get_or_init(|| Logger {
+        format: settings.format,
+        level: settings.level_filter(),
+        modules,
+        redaction: Redaction::from_config(settings),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.modules.iter().map(|(_, level)| *level).fold(logger.level, Ord::max));
+    }
+}
+
+/// `secret` as it may appear in a log line
+pub fn password(secret: &str) -> Cow<'_, str> {
+    match LOGGER.get() {
+        Some(logger) if !logger.redaction.password => Cow::Borrowed(secret),
+        _ => Cow::Borrowed("[redacted]"),
+    }
+}
+
+/// ID of the request being handled, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// The client's X-Request-Id if it is short and plain, else a new one
+fn request_id(req: &ServiceRequest) -> String {
+    req.headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID)
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+}
+
+fn header_text(req: &actix_web::HttpRequest, name: header::HeaderName) -> &str {
+    req.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or("-")
+}
+
+/// Middleware giving each request its ID and writing the access log. Wrap it
+/// outside everything else, so every line logged for the request carries the
+/// ID.
+pub async fn requests<B: MessageBody>(req: ServiceRequest, next: Next<B>) -> Result<ServiceResponse<B>, Error> {
+    let id = request_id(&req);
+    let started = Instant::now();
+    let mut res = REQUEST_ID.scope(id.clone(), next.call(req)).await?;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    if crate::config::get().log.access_log && log::log_enabled!(target: ACCESS_TARGET, Level::Info) {
+        if let Some(logger) = LOGGER.get() {
+            REQUEST_ID.sync_scope(id, || eprintln!("{}", access_line(logger, &res, started)));
+        }
+    }
+    Ok(res)
+}
+
+fn access_line<B: MessageBody>(logger: &Logger, res: &ServiceResponse<B>, started: Instant) -> String {
+    let req = res.request();
+    let redact = |text: &str| logger.redaction.apply(text).into_owned();
+    let ip = redact(req.connection_info().realip_remote_addr().unwrap_or("-"));
+    let target = redact(&req.uri().to_string());
+    let bytes = match res.response().body().size() {
+        BodySize::Sized(bytes) => Some(bytes),
+        _ => None,
+    };
+    let millis = started.elapsed().as_secs_f64() * 1000.0;
+    let referer = redact(header_text(req, header::REFERER));
+    let user_agent = header_text(req, header::USER_AGENT);
+    let message = format!(
+        "{} \"{} {} {:?}\" {} {} \"{}\" \"{}\" {:.3}ms",
+        ip,
+        req.method(),
+        target,
+        req.version(),
+        res.status().as_u16(),
+        bytes.map(|b| b.to_string()).unwrap_or_else(|| "-".to_string()),
+        referer,
+        user_agent,
+        millis,
+    );
+    logger.line(
+        Level::Info,
+        ACCESS_TARGET,
+        &message,
+        &[
+            ("ip", ip.into()),
+            ("method", req.method().as_str().into()),
+            ("path", target.into()),
+            ("status", res.status().as_u16().into()),
+            ("bytes", bytes.into()),
+            ("duration_ms", ((millis * 1000.0).round() / 1000.0).into()),
+            ("referer", referer.into()),
+            ("user_agent", user_agent.into()),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware::from_fn, web, App, HttpResponse};
+
+    fn all() -> Redaction {
+        Redaction { email: true, phone: true, password: true, ip: true }
+    }
+
+    #[test]
+    fn test_redaction() {
+        let line = "Honeypot triggered: jane.doe@example.com / (555) 123-4567 from 203.0.113.9 and [2001:db8::1]:443";
+        assert_eq!(all().apply(line), "Honeypot triggered: [email] / [phone] from [ip] and [[ip]]:443");
+        assert_eq!(Redaction { ip: true, ..Default::default() }.apply("a@b.io 555-123-4567 ::1"), "a@b.io 555-123-4567 [ip]");
+
+        // Things that only look similar are left alone
+        let untouched = "scc_server::decoys at 14:03:22 on 2026-01-05, v1.0.13, 999.1.1.1, id 1792361306";
+        assert_eq!(Redaction { ip: true, email: true, ..Default::default() }.apply(untouched), untouched);
+    }
+
+    #[test]
+    fn test_module_levels() {
+        let logger = Logger {
+            format: LogFormat::Text,
+            level: LevelFilter::Info,
+            modules: vec![("scc_server::decoys".to_string(), LevelFilter::Warn), ("scc_server".to_string(), LevelFilter::Debug)],
+            redaction: Redaction::default(),
+        };
+        assert_eq!(logger.level_for("scc_server::decoys"), LevelFilter::Warn);
+        assert_eq!(logger.level_for("scc_server::decoys::tarpit"), LevelFilter::Warn);
+        assert_eq!(logger.level_for("scc_server::billing"), LevelFilter::Debug);
+        assert_eq!(logger.level_for("scc_serverless"), LevelFilter::Info);
+        assert_eq!(logger.level_for("actix_server::worker"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn test_json_line() {
+        let logger = Logger { format: LogFormat::Json, level: LevelFilter::Info, modules: Vec::new(), redaction: all() };
+        let line = REQUEST_ID.sync_scope("abc123".to_string(), || {
+            logger.line(Level::Warn, "scc_server::billing", "Quote for jane@example.com", &[("status", 200.into())])
+        });
+        let line: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["request_id"], "abc123");
+        assert_eq!(line["msg"], "Quote for [email]");
+        assert_eq!(line["status"], 200);
+    }
+
+    #[actix_web::test]
+    async fn test_request_id_propagated() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(requests))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().body(current_request_id().unwrap_or_default()) })),
+        )
+        .await;
+        let res = call_service(&app, TestRequest::get().uri("/").insert_header((REQUEST_ID_HEADER, "edge-42")).to_request()).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "edge-42");
+        assert_eq!(actix_web::test::read_body(res).await, "edge-42");
+
+        let res = call_service(&app, TestRequest::get().uri("/").insert_header((REQUEST_ID_HEADER, "bad id\"<x>")).to_request()).await;
+        let id = res.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert_eq!(id.len(), 32);
+        assert_eq!(actix_web::test::read_body(res).await, id);
+    }
+}
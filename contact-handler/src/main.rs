@@ -1,23 +1,14 @@
-use actix_cors::Cors;
-use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, middleware, http::header};
-use base64::Engine;
-use chrono::Local;
-use mime_guess::from_path;
-use printpdf::*;
-use rust_embed::RustEmbed;
-use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::Path;
-use uuid::Uuid;
-
-// Include all the shared code
-include!("shared.rs");
+use actix_web::HttpServer;
+use scc_server::{assets, config, lifecycle, logging, overlay, tls};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let port = std::env::var("PORT").unwrap_or_else(|_| "9000".to_string());
-    let bind_addr = format!("0.0.0.0:{}", port);
+    let cli = config::init("scc-server", "0.0.0.0:9000", " [migrate]");
+    let config = config::get();
+    logging::init(&config.log);
+    if cli.args.first().map(String::as_str) == Some("migrate") {
+        return scc_server::run_migrate();
+    }
 
     println!("╔═══════════════════════════════════════════════════════════╗");
     println!("║     SOUTH CITY COMPUTER - Single Binary Web Server        ║");
@@ -25,9 +16,34 @@ async fn main() -> std::io::Result<()> {
     println!("║  All assets embedded in binary - zero disk reads          ║");
     println!("╚═══════════════════════════════════════════════════════════╝");
     println!();
-    println!("Starting server on http://{}", bind_addr);
+    let tls_config = tls::init(&config.tls).unwrap_or_else(|e| {
+        eprintln!("scc-server: TLS certificate: {}", e);
+        std::process::exit(2);
+    });
+    let listeners = lifecycle::listeners();
+    if listeners.is_empty() {
+        for addr in &config.server.bind {
+            println!("Starting server on http://{}", addr);
+        }
+        if tls_config.is_some() {
+            for addr in &config.tls.bind {
+                println!("Starting server on https://{}", addr);
+            }
+        }
+    }
+    for (name, listener) in &listeners {
+        let scheme = if name == "https" { "https" } else { "http" };
+        println!("Starting server on {}://{} (socket from systemd)", scheme, listener.local_addr()?);
+    }
+    println!("Data directory: {}", config.storage.data_dir.display());
+    if let Some(overlay) = overlay::get() {
+        overlay.report();
+        assets::init(overlay);
+    }
 
-    list_embedded_assets();
+    scc_server::list_assets();
+    scc_server::check_schemas();
+    scc_server::list_decoys();
 
     println!("API Endpoints:");
     println!("  POST /api/contact         - Submit contact form");
@@ -36,33 +52,41 @@ async fn main() -> std::io::Result<()> {
     println!("  GET  /view/{{id}}/pdf       - Download PDF");
     println!("  GET  /whitepaper/pdf/{{lang}} - Download whitepaper PDF");
     println!("  GET  /contact-admin       - Admin panel (auth required)");
+    println!("  GET  /contact-admin/quotes - Quotes & invoices (auth required)");
+    println!("  GET  /contact-admin/scan  - Scan a document QR code (auth required)");
+    println!("  GET  /contact-admin/overlay - Overridden assets (auth required)");
+    println!("  GET  /contact-admin/analytics - Page views (auth required)");
+    println!("  GET  /sitemap.xml         - Sitemap of the embedded pages");
+    println!("  GET  /robots.txt          - Crawler rules");
     println!("  GET  /health              - Health check");
+    println!("  GET  /metrics             - Prometheus metrics (loopback or token)");
     println!("  GET  /*                   - Embedded static files");
     println!();
 
-    HttpServer::new(|| {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
-
-        App::new()
-            .wrap(cors)
-            .wrap(middleware::Compress::default())
-            .wrap(middleware::Logger::default())
-            .route("/api/contact", web::post().to(handle_contact))
-            .route("/api/service-inquiry", web::post().to(handle_service_inquiry))
-            .route("/api/honeypot", web::post().to(handle_honeypot))
-            .route("/view/{id}", web::get().to(view_submission))
-            .route("/view/{id}/pdf", web::get().to(download_pdf))
-            .route("/whitepaper/pdf/{lang}", web::get().to(download_whitepaper_pdf))
-            .route("/contact-admin", web::get().to(contact_admin))
-            .route("/health", web::get().to(health_check))
-            .route("/", web::get().to(serve_index))
-            .default_service(web::get().to(serve_embedded))
-    })
-    .bind(&bind_addr)?
-    .run()
-    .await
+    let mut server = HttpServer::new(scc_server::app).disable_signals().shutdown_timeout(config.server.shutdown_timeout);
+    if listeners.is_empty() {
+        for addr in &config.server.bind {
+            server = server.bind(addr)?;
+        }
+        if let Some(tls_config) = &tls_config {
+            for addr in &config.tls.bind {
+                server = server.bind_rustls_0_23(addr, tls_config.clone())?;
+            }
+        }
+    }
+    for (name, listener) in listeners {
+        server = match (&tls_config, name.as_str()) {
+            (Some(tls_config), "https") => server.listen_rustls_0_23(listener, tls_config.clone())?,
+            (None, "https") => {
+                eprintln!("scc-server: systemd passed an \"https\" socket but tls.cert is not set");
+                std::process::exit(2);
+            }
+            _ => server.listen(listener)?,
+        };
+    }
+    // Redirects name the HTTPS port when it isn't 443
+    if let Some(port) = server.addrs_with_scheme().iter().find(|(_, scheme)| *scheme == "https").map(|(addr, _)| addr.port()) {
+        tls::set_https_port(port);
+    }
+    lifecycle::serve(server.run()).await
 }
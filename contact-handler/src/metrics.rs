@@ -0,0 +1,349 @@
+// Prometheus metrics
+// /metrics serves the server's counters in the Prometheus text format:
+//   scc_http_requests_total{route,method,status}
+//   scc_http_request_duration_seconds{route,method,status} (histogram)
+//   scc_http_requests_in_flight
+//   scc_asset_bytes_served_total{path}
+//   scc_records_stored_total{store}        (submissions, honeypot attempts, quotes, ...)
+//   scc_storage_write_errors_total{store}
+//   scc_honeypot_hits_total{source}
+//   scc_tarpit_streams                     (decoy responses still being dripped out)
+//   scc_build_info{version}
+// Requests are labelled with the pattern their route was registered under in
+// app() ("/view/{id}"), or "/*" for the static files, so record IDs and
+// scanner paths never turn into label values. Label sets a client can grow
+// (honeypot sources) are capped, with the rest counted as "other".
+//
+// Scrapers need an address in metrics.allow (loopback by default) or an
+// "Authorization: Bearer <metrics.token>" header; everyone else gets a 403.
+//
+// Configuration ([metrics] in config.rs):
+//   token - Bearer token accepted from any address (METRICS_TOKEN)
+//   allow - Addresses or networks allowed without it (METRICS_ALLOW)
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Instant;
+
+use crate::config;
+
+/// Route label for requests no registered route matched: the static files
+const STATIC_ROUTE: &str = "/*";
+
+const METHODS: &[&str] = &["GET", "HEAD", "POST", "PUT", "DELETE", "OPTIONS", "PATCH"];
+
+/// Upper bounds (seconds) of the latency histogram buckets
+const BUCKETS: [f64; 12] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Distinct label values kept per family before the rest count as "other"
+const MAX_SERIES: usize = 64;
+
+#[derive(Default)]
+struct Histogram {
+    /// Cumulative: each bucket counts every observation up to its bound
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        for (bucket, le) in self.buckets.iter_mut().zip(BUCKETS) {
+            if secs <= le {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+}
+
+type RequestKey = (String, &'static str, u16);
+
+#[derive(Default)]
+struct Metrics {
+    requests: Mutex<BTreeMap<RequestKey, Histogram>>,
+    in_flight: AtomicI64,
+    asset_bytes: Mutex<BTreeMap<String, u64>>,
+    stored: Mutex<BTreeMap<String, u64>>,
+    write_errors: Mutex<BTreeMap<String, u64>>,
+    honeypot_hits: Mutex<BTreeMap<String, u64>>,
+    tarpit_streams: AtomicI64,
+}
+
+fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn add(counters: &Mutex<BTreeMap<String, u64>>, label: &str, n: u64) {
+    let mut counters = lock(counters);
+    let label = if counters.contains_key(label) || counters.len() < MAX_SERIES { label } else { "other" };
+    *counters.entry(label.to_string()).or_insert(0) += n;
+}
+
+/// Counts itself in a gauge for as long as it lives
+pub struct Gauge(&'static AtomicI64);
+
+impl Gauge {
+    fn enter(gauge: &'static AtomicI64) -> Gauge {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Gauge(gauge)
+    }
+}
+
+impl Drop for Gauge {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// `bytes` of the asset at `path` sent to a client
+pub fn asset_served(path: &str, bytes: u64) {
+    add(&metrics().asset_bytes, path, bytes);
+}
+
+/// One record written to (or failed to be written to) `store`
+pub fn record_write(store: &str, ok: bool) {
+    let counters = if ok { &metrics().stored } else { &metrics().write_errors };
+    add(counters, store, 1);
+}
+
+pub fn honeypot_hit(source: &str) {
+    add(&metrics().honeypot_hits, source, 1);
+}
+
+/// Hold while a tarpitted decoy response is being sent
+pub fn tarpit_stream() -> Gauge {
+    Gauge::enter(&metrics().tarpit_streams)
+}
+
+/// Requests being handled right now, this one included
+pub fn in_flight() -> i64 {
+    metrics().in_flight.load(Ordering::Relaxed)
+}
+
+pub fn tarpit_streams() -> i64 {
+    metrics().tarpit_streams.load(Ordering::Relaxed)
+}
+
+fn method_label(method: &str) -> &'static str {
+    METHODS.iter().copied().find(|m| *m == method).unwrap_or("OTHER")
+}
+
+/// Middleware counting every request by route pattern, method and status
+pub async fn track<B: MessageBody>(req: ServiceRequest, next: Next<B>) -> Result<ServiceResponse<B>, Error> {
+    let _in_flight = Gauge::enter(&metrics().in_flight);
+    let method = method_label(req.method().as_str());
+    let started = Instant::now();
+    let res = next.call(req).await;
+    let secs = started.elapsed().as_secs_f64();
+    let (route, status) = match &res {
+        Ok(res) => (res.request().match_pattern().unwrap_or_else(|| STATIC_ROUTE.to_string()), res.status().as_u16()),
+        Err(e) => (STATIC_ROUTE.to_string(), e.as_response_error().status_code().as_u16()),
+    };
+    lock(&metrics().requests).entry((route, method, status)).or_default().observe(secs);
+    res
+}
+
+/// Label value with the characters the text format reserves escaped
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn counters(out: &mut String, name: &str, help: &str, label: &str, counters: &Mutex<BTreeMap<String, u64>>) {
+    family(out, name, "counter", help);
+    for (value, n) in lock(counters).iter() {
+        let _ = writeln!(out, "{}{{{}=\"{}\"}} {}", name, label, escape(value), n);
+    }
+}
+
+fn render() -> String {
+    let m = metrics();
+    let mut out = String::new();
+    let requests = lock(&m.requests);
+
+    family(&mut out, "scc_http_requests_total", "counter", "Requests handled, by route pattern, method and status.");
+    for ((route, method, status), histogram) in requests.iter() {
+        let _ = writeln!(out, "scc_http_requests_total{{route=\"{}\",method=\"{}\",status=\"{}\"}} {}", escape(route), method, status, histogram.count);
+    }
+
+    let name = "scc_http_request_duration_seconds";
+    family(&mut out, name, "histogram", "Time to produce a response, by route pattern, method and status.");
+    for ((route, method, status), histogram) in requests.iter() {
+        let labels = format!("route=\"{}\",method=\"{}\",status=\"{}\"", escape(route), method, status);
+        for (le, n) in BUCKETS.iter().zip(histogram.buckets) {
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, le, n);
+        }
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", name, labels, histogram.count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count);
+    }
+    drop(requests);
+
+    family(&mut out, "scc_http_requests_in_flight", "gauge", "Requests being handled right now.");
+    let _ = writeln!(out, "scc_http_requests_in_flight {}", m.in_flight.load(Ordering::Relaxed));
+
+    counters(&mut out, "scc_asset_bytes_served_total", "Response body bytes sent per static asset.", "path", &m.asset_bytes);
+    counters(&mut out, "scc_records_stored_total", "Records written, per store.", "store", &m.stored);
+    counters(&mut out, "scc_storage_write_errors_total", "Records that could not be written, per store.", "store", &m.write_errors);
+    counters(&mut out, "scc_honeypot_hits_total", "Honeypot and decoy hits, per source.", "source", &m.honeypot_hits);
+
+    family(&mut out, "scc_tarpit_streams", "gauge", "Decoy responses still being dripped out to scanners.");
+    let _ = writeln!(out, "scc_tarpit_streams {}", m.tarpit_streams.load(Ordering::Relaxed));
+
+    family(&mut out, "scc_build_info", "gauge", "Version of the running binary.");
+    let _ = writeln!(out, "scc_build_info{{version=\"{}\"}} 1", env!("CARGO_PKG_VERSION"));
+    out
+}
+
+/// "10.0.0.5" or "10.0.0.0/8" as an address and prefix length
+pub(crate) fn parse_network(entry: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (entry, None),
+    };
+    let addr: IpAddr = addr.trim().parse().ok()?;
+    let bits = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix.trim().parse().ok().filter(|&prefix| prefix <= bits)?,
+        None => bits,
+    };
+    Some((addr, prefix))
+}
+
+pub(crate) fn in_network(ip: IpAddr, (network, prefix): (IpAddr, u8)) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => (u32::from(ip) ^ u32::from(network)).checked_shr(32 - prefix as u32).unwrap_or(0) == 0,
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip) ^ u128::from(network)).checked_shr(128 - prefix as u32).unwrap_or(0) == 0,
+        _ => false,
+    }
+}
+
+/// Compare without stopping at the first differing byte
+fn same_token(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Whether `req` carries the token or comes from an allowed address. The peer
+/// address is used, not X-Forwarded-For, which any client can send.
+fn authorized(req: &HttpRequest, token: Option<&str>, allow: &[(IpAddr, u8)]) -> bool {
+    let bearer = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()).and_then(|v| v.strip_prefix("Bearer "));
+    if let (Some(token), Some(bearer)) = (token, bearer) {
+        if same_token(bearer.trim(), token) {
+            return true;
+        }
+    }
+    req.peer_addr().is_some_and(|peer| allow.iter().any(|&network| in_network(peer.ip().to_canonical(), network)))
+}
+
+fn allowed_networks() -> &'static [(IpAddr, u8)] {
+    static NETWORKS: OnceLock<Vec<(IpAddr, u8)>> = OnceLock::new();
+    NETWORKS.get_or_init(|| config::get().metrics.allow.iter().filter_map(|entry| parse_network(entry)).collect())
+}
+
+/// Whether `req` may scrape /metrics (and see /health/ready's details)
+pub(crate) fn scraper(req: &HttpRequest) -> bool {
+    authorized(req, config::get().metrics.token.as_deref(), allowed_networks())
+}
+
+async fn serve_metrics(req: HttpRequest) -> HttpResponse {
+    if !scraper(&req) {
+        return HttpResponse::Forbidden().content_type("text/plain; charset=utf-8").body("Forbidden");
+    }
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .body(render())
+}
+
+pub fn configure_metrics(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(serve_metrics));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware::from_fn, App};
+
+    #[test]
+    fn test_networks() {
+        let lan = parse_network("10.0.0.0/8").unwrap();
+        assert!(in_network("10.1.2.3".parse().unwrap(), lan));
+        assert!(!in_network("11.0.0.1".parse().unwrap(), lan));
+        assert!(!in_network("::1".parse().unwrap(), lan));
+        assert!(in_network("::1".parse().unwrap(), parse_network("::1").unwrap()));
+        assert!(in_network("192.0.2.1".parse().unwrap(), parse_network("0.0.0.0/0").unwrap()));
+        assert!(parse_network("10.0.0.0/33").is_none());
+        assert!(parse_network("localhost").is_none());
+    }
+
+    #[test]
+    fn test_authorized() {
+        let allow = [parse_network("127.0.0.1").unwrap()];
+        let local = TestRequest::default().peer_addr("127.0.0.1:5000".parse().unwrap()).to_http_request();
+        assert!(authorized(&local, None, &allow));
+        let mapped = TestRequest::default().peer_addr("[::ffff:127.0.0.1]:5000".parse().unwrap()).to_http_request();
+        assert!(authorized(&mapped, None, &allow));
+
+        let remote = TestRequest::default()
+            .peer_addr("203.0.113.9:5000".parse().unwrap())
+            .insert_header(("X-Forwarded-For", "127.0.0.1"))
+            .insert_header((header::AUTHORIZATION, "Bearer s3cret"));
+        assert!(!authorized(&remote.to_http_request(), None, &allow));
+        let remote = TestRequest::default().peer_addr("203.0.113.9:5000".parse().unwrap()).insert_header((header::AUTHORIZATION, "Bearer s3cret"));
+        assert!(authorized(&remote.to_http_request(), Some("s3cret"), &allow));
+        assert!(!authorized(&TestRequest::default().insert_header((header::AUTHORIZATION, "Bearer nope")).to_http_request(), Some("s3cret"), &allow));
+    }
+
+    #[actix_web::test]
+    async fn test_requests_labelled_by_route_pattern() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(track))
+                .route("/metrics-test/{id}", web::get().to(HttpResponse::Ok))
+                .default_service(web::to(HttpResponse::NotFound)),
+        )
+        .await;
+        call_service(&app, TestRequest::get().uri("/metrics-test/a1b2c3d4").to_request()).await;
+        call_service(&app, TestRequest::default().method(actix_web::http::Method::from_bytes(b"BREW").unwrap()).uri("/metrics-test/x").to_request()).await;
+        call_service(&app, TestRequest::get().uri("/metrics-test-missing.php").to_request()).await;
+
+        let text = render();
+        assert!(text.contains("scc_http_requests_total{route=\"/metrics-test/{id}\",method=\"GET\",status=\"200\"} 1"));
+        assert!(text.contains("route=\"/metrics-test/{id}\",method=\"OTHER\""));
+        assert!(text.contains("scc_http_request_duration_seconds_count{route=\"/metrics-test/{id}\",method=\"GET\",status=\"200\"} 1"));
+        assert!(text.contains("route=\"/*\",method=\"GET\",status=\"404\""));
+        assert!(!text.contains("a1b2c3d4") && !text.contains("missing.php"));
+    }
+
+    #[test]
+    fn test_label_values_capped_and_escaped() {
+        for n in 0..MAX_SERIES + 5 {
+            honeypot_hit(&format!("scanner-{}", n));
+        }
+        record_write("test \"store\"", false);
+        let text = render();
+        assert!(text.contains("scc_honeypot_hits_total{source=\"other\"}"));
+        assert!(text.lines().filter(|line| line.starts_with("scc_honeypot_hits_total{")).count() <= MAX_SERIES + 1);
+        assert!(text.contains("scc_storage_write_errors_total{store=\"test \\\"store\\\"\"} 1"));
+    }
+}
@@ -0,0 +1,384 @@
+// Disk overlay for the embedded assets
+// A hotfix for a typo on a service page, or a seasonal banner, can go live
+// without a rebuild: a file in overlay.dir with the same path as an embedded
+// one is served in its place. Only paths matching overlay.allow (pages and
+// images by default) that the build would also embed are ever read from the
+// overlay, so scripts, styles and anything outside the site can't be swapped
+// in. Every override is logged with its SHA-256 next to the embedded file's,
+// at startup and whenever its content changes.
+//
+// /contact-admin/overlay lists the files currently overridden and clears them
+// (the file is renamed to "<name>.cleared-<timestamp>", so it stops being
+// served but isn't lost); /api/overlay returns the same list as JSON.
+//
+// Configuration ([overlay] in config.rs):
+//   dir   - Overlay directory (OVERLAY_DIR); unset for none
+//   allow - Glob patterns for paths that may be overridden (OVERLAY_ALLOW)
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Local};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rust_embed::EmbeddedFile;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::assets::{AssetSource, DiskAssets, EmbeddedAssets};
+use crate::billing::{admin_page, unauthorized, ADMIN_STYLE};
+use crate::{check_auth, config, html_escape, lang};
+
+fn hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// One file currently served from the overlay
+#[derive(Debug, Serialize)]
+pub struct Override {
+    pub path: String,
+    pub size: usize,
+    pub sha256: String,
+    /// SHA-256 of the embedded file it replaces; None for a path the build
+    /// doesn't have
+    pub embedded_sha256: Option<String>,
+    /// Unix time the overlay file last changed
+    pub modified: Option<u64>,
+}
+
+pub struct OverlayAssets {
+    base: EmbeddedAssets,
+    disk: DiskAssets,
+    allow: GlobSet,
+    /// Hash last logged per overridden path
+    seen: Mutex<HashMap<String, [u8; 32]>>,
+}
+
+impl OverlayAssets {
+    pub fn new(dir: impl Into<PathBuf>, allow: &[String]) -> Self {
+        let mut patterns = GlobSetBuilder::new();
+        for pattern in allow {
+            // config::validate has already rejected bad patterns
+            if let Ok(glob) = Glob::new(pattern) {
+                patterns.add(glob);
+            }
+        }
+        OverlayAssets {
+            base: EmbeddedAssets,
+            disk: DiskAssets::new(dir),
+            allow: patterns.build().unwrap_or_else(|_| GlobSet::empty()),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.disk.root()
+    }
+
+    fn allowed(&self, path: &str) -> bool {
+        self.allow.is_match(path)
+    }
+
+    /// The overlay's copy of `path`, logged the first time it's seen and
+    /// whenever it changes
+    fn override_for(&self, path: &str) -> Option<EmbeddedFile> {
+        if !self.allowed(path) {
+            return None;
+        }
+        let file = self.disk.get(path)?;
+        let hash = file.metadata.sha256_hash();
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.get(path) != Some(&hash) {
+            let embedded = self.base.get(path).map(|f| hex(&f.metadata.sha256_hash())).unwrap_or_else(|| "none".to_string());
+            log::info!("Overlay: serving {} ({} bytes, sha256 {}; embedded sha256 {})", path, file.data.len(), hex(&hash), embedded);
+            seen.insert(path.to_string(), hash);
+        }
+        Some(file)
+    }
+
+    /// Files in the overlay directory that would be served: (overrides,
+    /// files ignored because the allowlist or the embed patterns refuse them)
+    fn scan(&self) -> (Vec<Override>, Vec<String>) {
+        let mut overrides = Vec::new();
+        let mut ignored = Vec::new();
+        for path in walk(self.dir()) {
+            let Some(file) = self.allowed(&path).then(|| self.disk.get(&path)).flatten() else {
+                ignored.push(path);
+                continue;
+            };
+            overrides.push(Override {
+                size: file.data.len(),
+                sha256: hex(&file.metadata.sha256_hash()),
+                embedded_sha256: self.base.get(&path).map(|f| hex(&f.metadata.sha256_hash())),
+                modified: file.metadata.last_modified(),
+                path,
+            });
+        }
+        (overrides, ignored)
+    }
+
+    pub fn overrides(&self) -> Vec<Override> {
+        self.scan().0
+    }
+
+    /// Print what the overlay replaces, and what it holds but won't serve
+    pub fn report(&self) {
+        let (overrides, ignored) = self.scan();
+        println!("Overlay directory: {} ({} overrides)", self.dir().display(), overrides.len());
+        for file in &overrides {
+            println!(
+                "  {} ({} bytes, sha256 {}; embedded sha256 {})",
+                file.path,
+                file.size,
+                file.sha256,
+                file.embedded_sha256.as_deref().unwrap_or("none")
+            );
+        }
+        for path in ignored {
+            log::warn!("Overlay: ignoring {} (not in overlay.allow or not a site file)", path);
+        }
+        println!();
+    }
+
+    /// Stop serving the overlay's copy of `path`, keeping it next to where it
+    /// was. Returns the new name, or None if `path` isn't overridden.
+    pub fn clear(&self, path: &str) -> io::Result<Option<PathBuf>> {
+        if !self.allowed(path) || self.disk.get(path).is_none() {
+            return Ok(None);
+        }
+        let file = self.dir().join(path);
+        let cleared = PathBuf::from(format!("{}.cleared-{}", file.display(), Local::now().format("%Y%m%d-%H%M%S")));
+        std::fs::rename(&file, &cleared)?;
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).remove(path);
+        log::warn!("Overlay: cleared {} (kept as {})", path, cleared.display());
+        Ok(Some(cleared))
+    }
+}
+
+/// Every file under `dir` as a "/"-separated relative path
+fn walk(dir: &Path) -> Vec<String> {
+    fn visit(root: &Path, dir: &Path, found: &mut Vec<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                visit(root, &path, found);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                found.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    let mut found = Vec::new();
+    visit(dir, dir, &mut found);
+    found.sort();
+    found
+}
+
+impl AssetSource for OverlayAssets {
+    fn get(&self, path: &str) -> Option<EmbeddedFile> {
+        self.override_for(path).or_else(|| self.base.get(path))
+    }
+
+    fn paths(&self) -> Vec<String> {
+        let mut paths = self.base.paths();
+        for file in self.overrides() {
+            if !paths.contains(&file.path) {
+                paths.push(file.path);
+            }
+        }
+        paths
+    }
+
+    /// Embedded files date from the build; an overlay file from whenever it
+    /// was put there, which is always later
+    fn last_modified(&self, file: &EmbeddedFile) -> SystemTime {
+        let modified = file.metadata.last_modified().map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        self.base.last_modified(file).max(modified.unwrap_or(UNIX_EPOCH))
+    }
+}
+
+/// The overlay from overlay.dir, if one is configured
+pub fn get() -> Option<&'static OverlayAssets> {
+    static OVERLAY: OnceLock<Option<OverlayAssets>> = OnceLock::new();
+    OVERLAY
+        .get_or_init(|| {
+            let settings = &config::get().overlay;
+            settings.dir.as_ref().map(|dir| OverlayAssets::new(dir, &settings.allow))
+        })
+        .as_ref()
+}
+
+#[derive(Deserialize)]
+struct ClearForm {
+    /// Empty to clear every override
+    #[serde(default)]
+    path: String,
+}
+
+async fn overlay_admin(req: HttpRequest) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let lang = lang::negotiate(&req);
+    let t = |en, es| lang.pick(en, es);
+    let body = match get() {
+        None => format!(
+            "<p>{} (<code>overlay.dir</code> / <code>OVERLAY_DIR</code>).</p>",
+            t("No overlay directory is configured", "No hay un directorio de superposición configurado")
+        ),
+        Some(overlay) => {
+            let overrides = overlay.overrides();
+            let rows: String = overrides
+                .iter()
+                .map(|file| {
+                    let modified = file
+                        .modified
+                        .and_then(|secs| DateTime::from_timestamp(secs as i64, 0))
+                        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default();
+                    format!(
+                        r#"<tr><td><a href="/{path}">{path}</a></td><td class="num">{size}</td><td>{modified}</td><td><code>{sha}</code></td><td><code>{embedded}</code></td>
+<td><form method="post" action="/contact-admin/overlay/clear"><input type="hidden" name="path" value="{path}"><button class="secondary">{t_clear}</button></form></td></tr>"#,
+                        path = html_escape(&file.path),
+                        size = file.size,
+                        modified = modified,
+                        sha = &file.sha256[..16],
+                        embedded = file.embedded_sha256.as_deref().map(|sha| &sha[..16]).unwrap_or(t("new file", "archivo nuevo")),
+                        t_clear = t("Clear", "Quitar"),
+                    )
+                })
+                .collect();
+            let clear_all = if overrides.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    r#"<form method="post" action="/contact-admin/overlay/clear"><input type="hidden" name="path" value=""><button>{}</button></form>"#,
+                    t("Clear all", "Quitar todos")
+                )
+            };
+            format!(
+                r#"<p class="card">{t_serving} <code>{dir}</code> {t_in_place}</p>
+<table><thead><tr><th>{t_path}</th><th>Bytes</th><th>{t_modified}</th><th>SHA-256</th><th>{t_embedded}</th><th></th></tr></thead>
+<tbody>{rows}</tbody></table>{clear_all}"#,
+                dir = html_escape(&overlay.dir().display().to_string()),
+                t_serving = t("Serving files from", "Sirviendo archivos de"),
+                t_in_place = t("in place of the embedded ones.", "en lugar de los integrados."),
+                rows = if rows.is_empty() {
+                    format!(r#"<tr><td colspan="6">{}</td></tr>"#, t("No files are overridden.", "No se reemplaza ningún archivo."))
+                } else {
+                    rows
+                },
+                clear_all = clear_all,
+                t_path = t("Path", "Ruta"),
+                t_modified = t("Modified", "Modificado"),
+                t_embedded = t("Embedded", "Integrado"),
+            )
+        }
+    };
+    admin_page(lang, format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>{style}</style>
+</head>
+<body>
+    <div class="container">
+        <a class="back-link" href="/contact-admin">&larr; {t_back}</a>
+        <h1>{t_title}</h1>
+        {body}
+    </div>
+</body>
+</html>"#,
+        lang = lang.code(),
+        style = ADMIN_STYLE,
+        body = body,
+        t_title = t("Asset Overlay", "Superposición de archivos"),
+        t_back = t("Back to Admin", "Volver al panel"),
+    ))
+}
+
+async fn clear_overlay(req: HttpRequest, form: web::Form<ClearForm>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let Some(overlay) = get() else {
+        return HttpResponse::NotFound().body("No overlay directory is configured");
+    };
+    let paths = if form.path.is_empty() { overlay.overrides().into_iter().map(|file| file.path).collect() } else { vec![form.path.clone()] };
+    for path in paths {
+        if let Err(e) = overlay.clear(&path) {
+            log::error!("Overlay: could not clear {}: {}", path, e);
+            return HttpResponse::InternalServerError().body(format!("Could not clear {}", path));
+        }
+    }
+    HttpResponse::SeeOther().insert_header(("Location", "/contact-admin/overlay")).finish()
+}
+
+async fn overlay_json(req: HttpRequest) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    HttpResponse::Ok().json(get().map(|overlay| overlay.overrides()).unwrap_or_default())
+}
+
+pub fn configure_overlay(cfg: &mut web::ServiceConfig) {
+    cfg.route("/contact-admin/overlay", web::get().to(overlay_admin))
+        .route("/contact-admin/overlay/clear", web::post().to(clear_overlay))
+        .route("/api/overlay", web::get().to(overlay_json));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh overlay directory under the system temp dir
+    fn overlay_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scc-overlay-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("services")).unwrap();
+        std::fs::create_dir_all(dir.join("css")).unwrap();
+        dir
+    }
+
+    fn default_allow() -> Vec<String> {
+        config::Config::default().overlay.allow
+    }
+
+    #[test]
+    fn test_overlay_replaces_allowed_files_only() {
+        let dir = overlay_dir("allow");
+        std::fs::write(dir.join("services/data-recovery.html"), "<p>hotfix</p>").unwrap();
+        std::fs::write(dir.join("css/style.min.css"), "body{}").unwrap();
+        std::fs::write(dir.join("accounts.txt"), "admin:admin").unwrap();
+        let overlay = OverlayAssets::new(&dir, &default_allow());
+
+        assert_eq!(&overlay.get("services/data-recovery.html").unwrap().data[..], b"<p>hotfix</p>");
+        assert_ne!(&overlay.get("css/style.min.css").unwrap().data[..], b"body{}");
+        assert!(overlay.get("accounts.txt").is_none());
+
+        let (overrides, ignored) = overlay.scan();
+        assert_eq!(overrides.len(), 1);
+        assert!(overrides[0].embedded_sha256.is_some());
+        assert_eq!(ignored, vec!["accounts.txt".to_string(), "css/style.min.css".to_string()]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clear_keeps_a_copy() {
+        let dir = overlay_dir("clear");
+        std::fs::write(dir.join("index.html"), "<p>banner</p>").unwrap();
+        let overlay = OverlayAssets::new(&dir, &default_allow());
+        assert_eq!(&overlay.get("index.html").unwrap().data[..], b"<p>banner</p>");
+
+        let cleared = overlay.clear("index.html").unwrap().unwrap();
+        assert!(cleared.exists());
+        assert_ne!(&overlay.get("index.html").unwrap().data[..], b"<p>banner</p>");
+        assert!(overlay.overrides().is_empty());
+        assert!(overlay.clear("index.html").unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,741 @@
+// Small layout engine on top of printpdf
+// Wraps text by measured glyph width, breaks pages automatically, repeats the
+// header/footer on every page with "Page N of M", and embeds DejaVu Sans so
+// accented and non-Latin text renders (the builtin Helvetica is WinAnsi only).
+// Also handles mixed bold/monospace runs, code blocks and simple tables.
+
+use printpdf::lopdf;
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rect, Rgb,
+};
+
+const FONT_REGULAR: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+const FONT_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN_LEFT: f32 = 20.0;
+const MARGIN_RIGHT: f32 = 20.0;
+const MARGIN_TOP: f32 = 20.0;
+const MARGIN_BOTTOM: f32 = 25.0;
+const FOOTER_Y: f32 = 12.0;
+const PT_TO_MM: f32 = 25.4 / 72.0;
+const LINE_SPACING: f32 = 1.35;
+const QR_SIZE: f32 = 26.0;
+
+pub type PdfResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Glyph metrics for one embedded font, used to measure text before placing it.
+pub struct FontMetrics {
+    face: ttf_parser::Face<'static>,
+}
+
+impl FontMetrics {
+    fn new(data: &'static [u8]) -> PdfResult<Self> {
+        Ok(FontMetrics { face: ttf_parser::Face::parse(data, 0)? })
+    }
+
+    pub fn regular() -> PdfResult<Self> {
+        Self::new(FONT_REGULAR)
+    }
+
+    pub fn bold() -> PdfResult<Self> {
+        Self::new(FONT_BOLD)
+    }
+
+    pub fn mono() -> PdfResult<Self> {
+        Self::new(FONT_MONO)
+    }
+
+    /// Width of `text` in millimetres at `size` points
+    pub fn width_mm(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = text
+            .chars()
+            .map(|c| {
+                let glyph = self.face.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0));
+                self.face.glyph_hor_advance(glyph).unwrap_or(0) as u32
+            })
+            .sum();
+        units as f32 / self.face.units_per_em() as f32 * size * PT_TO_MM
+    }
+
+    /// Split `text` by character into pieces no wider than `max_width`
+    /// (each piece keeps at least one character).
+    pub fn break_chars(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let mut pieces = Vec::new();
+        let mut piece = String::new();
+        for c in text.chars() {
+            piece.push(c);
+            if self.width_mm(&piece, size) > max_width && piece.chars().count() > 1 {
+                piece.pop();
+                pieces.push(std::mem::take(&mut piece));
+                piece.push(c);
+            }
+        }
+        pieces.push(piece);
+        pieces
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Weight {
+    Regular,
+    Bold,
+    /// DejaVu Sans Mono; only embedded once `enable_mono` is called
+    Mono,
+}
+
+/// A run of text in one weight, for paragraphs with inline bold or code
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub weight: Weight,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, weight: Weight) -> Self {
+        Span { text: text.into(), weight }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// One wrapped line of rich text: (x offset, weight, text) runs
+pub type RunLine = Vec<(f32, Weight, String)>;
+
+struct Word {
+    text: String,
+    weight: Weight,
+    space_before: bool,
+}
+
+/// Split spans into words, remembering whether whitespace preceded each one
+/// so "**bold**," doesn't gain a space before the comma. `None` marks a '\n'.
+fn words(spans: &[Span]) -> Vec<Option<Word>> {
+    let mut out = Vec::new();
+    let mut pending_space = false;
+    for span in spans {
+        let mut current: Option<Word> = None;
+        for c in span.text.chars() {
+            if c == '\n' {
+                out.extend(current.take().map(Some));
+                out.push(None);
+                pending_space = false;
+            } else if c.is_whitespace() {
+                out.extend(current.take().map(Some));
+                pending_space = true;
+            } else {
+                if current.is_none() {
+                    current = Some(Word { text: String::new(), weight: span.weight, space_before: pending_space });
+                    pending_space = false;
+                }
+                if let Some(word) = current.as_mut() {
+                    word.text.push(c);
+                }
+            }
+        }
+        out.extend(current.map(Some));
+    }
+    out
+}
+
+/// Append to the line, merging with the previous run when the weight matches
+fn push_run(line: &mut RunLine, x: f32, weight: Weight, text: &str, space: bool) {
+    if let Some((_, last_weight, last_text)) = line.last_mut() {
+        if *last_weight == weight {
+            if space {
+                last_text.push(' ');
+            }
+            last_text.push_str(text);
+            return;
+        }
+    }
+    line.push((x, weight, text.to_string()));
+}
+
+/// Text repeated on every page. `page_label` is formatted as "{label} N / M".
+pub struct PageStyle {
+    pub header_title: String,
+    pub header_subtitle: String,
+    pub footer: String,
+    pub page_label: String,
+}
+
+const TABLE_PADDING: f32 = 1.5;
+
+struct TableGrid<'a> {
+    widths: Vec<f32>,
+    aligns: &'a [Align],
+    size: f32,
+    left: f32,
+}
+
+impl TableGrid<'_> {
+    fn row_height(&self, cells: &[Vec<RunLine>]) -> f32 {
+        let lines = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        lines as f32 * PdfLayout::line_height(self.size) + TABLE_PADDING * 2.0
+    }
+}
+
+/// Column widths that fit in `total`: columns narrower than an even share
+/// keep their natural width and the wider ones split what is left.
+fn fit_columns(natural: &[f32], total: f32) -> Vec<f32> {
+    if natural.iter().sum::<f32>() <= total {
+        return natural.to_vec();
+    }
+    let mut order: Vec<usize> = (0..natural.len()).collect();
+    order.sort_by(|a, b| natural[*a].total_cmp(&natural[*b]));
+    let mut widths = vec![0.0; natural.len()];
+    let mut remaining = total;
+    for (done, &column) in order.iter().enumerate() {
+        let share = remaining / (natural.len() - done) as f32;
+        widths[column] = natural[column].min(share);
+        remaining -= widths[column];
+    }
+    widths
+}
+
+pub struct PdfLayout {
+    doc: PdfDocumentReference,
+    style: PageStyle,
+    font: IndirectFontRef,
+    font_bold: IndirectFontRef,
+    metrics: FontMetrics,
+    metrics_bold: FontMetrics,
+    font_mono: Option<IndirectFontRef>,
+    metrics_mono: FontMetrics,
+    layers: Vec<PdfLayerReference>,
+    /// Top of the next line, in mm from the bottom of the page
+    cursor: f32,
+}
+
+impl PdfLayout {
+    pub fn new(title: &str, style: PageStyle) -> PdfResult<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        let font = doc.add_external_font(FONT_REGULAR)?;
+        let font_bold = doc.add_external_font(FONT_BOLD)?;
+        let first = doc.get_page(page).get_layer(layer);
+
+        let mut layout = PdfLayout {
+            doc,
+            style,
+            font,
+            font_bold,
+            metrics: FontMetrics::regular()?,
+            metrics_bold: FontMetrics::bold()?,
+            font_mono: None,
+            metrics_mono: FontMetrics::mono()?,
+            layers: vec![first],
+            cursor: PAGE_HEIGHT - MARGIN_TOP,
+        };
+        layout.draw_header();
+        Ok(layout)
+    }
+
+    pub fn content_width(&self) -> f32 {
+        PAGE_WIDTH - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    /// Embed the monospace face. Kept opt-in so documents without code don't
+    /// carry another font.
+    pub fn enable_mono(&mut self) -> PdfResult<()> {
+        if self.font_mono.is_none() {
+            self.font_mono = Some(self.doc.add_external_font(FONT_MONO)?);
+        }
+        Ok(())
+    }
+
+    fn mono_enabled(&self, weight: Weight) -> Weight {
+        match (weight, &self.font_mono) {
+            (Weight::Mono, None) => Weight::Regular,
+            _ => weight,
+        }
+    }
+
+    pub fn metrics(&self, weight: Weight) -> &FontMetrics {
+        match self.mono_enabled(weight) {
+            Weight::Regular => &self.metrics,
+            Weight::Bold => &self.metrics_bold,
+            Weight::Mono => &self.metrics_mono,
+        }
+    }
+
+    pub fn font(&self, weight: Weight) -> &IndirectFontRef {
+        match (self.mono_enabled(weight), &self.font_mono) {
+            (Weight::Bold, _) => &self.font_bold,
+            (Weight::Mono, Some(mono)) => mono,
+            _ => &self.font,
+        }
+    }
+
+    /// Layer of the page currently being filled, for drawing that isn't text flow
+    pub fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("layout always has a page")
+    }
+
+    #[cfg(test)]
+    pub fn page_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn draw_header(&mut self) {
+        let layer = self.layer().clone();
+        let mut y = PAGE_HEIGHT - MARGIN_TOP;
+        if !self.style.header_title.is_empty() {
+            y -= 16.0 * PT_TO_MM;
+            layer.use_text(&self.style.header_title, 16.0, Mm(MARGIN_LEFT), Mm(y), &self.font_bold);
+        }
+        if !self.style.header_subtitle.is_empty() {
+            y -= 6.0;
+            layer.use_text(&self.style.header_subtitle, 11.0, Mm(MARGIN_LEFT), Mm(y), &self.font);
+        }
+        self.cursor = y - 10.0;
+    }
+
+    pub fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), format!("Layer {}", self.layers.len() + 1));
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.draw_header();
+    }
+
+    /// Start a new page unless `height` mm still fit above the footer
+    pub fn ensure_space(&mut self, height: f32) {
+        if self.cursor - height < MARGIN_BOTTOM {
+            self.new_page();
+        }
+    }
+
+    pub fn space(&mut self, height: f32) {
+        self.cursor -= height;
+    }
+
+    pub fn line_height(size: f32) -> f32 {
+        size * PT_TO_MM * LINE_SPACING
+    }
+
+    /// Wrapped text at the left margin, breaking pages as needed
+    pub fn text(&mut self, text: &str, size: f32, weight: Weight) {
+        self.text_indented(text, size, weight, 0.0);
+    }
+
+    pub fn text_indented(&mut self, text: &str, size: f32, weight: Weight, indent: f32) {
+        self.rich_text(&[Span::new(text, weight)], size, indent, None);
+    }
+
+    /// Word-wrap mixed-weight text. Explicit newlines force a line break.
+    pub fn wrap_spans(&self, spans: &[Span], size: f32, max_width: f32) -> Vec<RunLine> {
+        let mut lines = Vec::new();
+        let mut line: RunLine = Vec::new();
+        let mut x = 0.0;
+
+        for word in words(spans) {
+            let Some(word) = word else {
+                lines.push(std::mem::take(&mut line));
+                x = 0.0;
+                continue;
+            };
+            let metrics = self.metrics(word.weight);
+            let width = metrics.width_mm(&word.text, size);
+            let space_width = metrics.width_mm(" ", size);
+            if !line.is_empty() && x + space_width + width > max_width {
+                lines.push(std::mem::take(&mut line));
+                x = 0.0;
+            }
+
+            let pieces = if width > max_width { metrics.break_chars(&word.text, size, max_width) } else { vec![word.text] };
+            let last = pieces.len() - 1;
+            for (i, piece) in pieces.iter().enumerate() {
+                let space = i == 0 && word.space_before && !line.is_empty();
+                if space {
+                    x += space_width;
+                }
+                push_run(&mut line, x, word.weight, piece, space);
+                x += metrics.width_mm(piece, size);
+                if i < last {
+                    lines.push(std::mem::take(&mut line));
+                    x = 0.0;
+                }
+            }
+        }
+
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Wrapped mixed-weight text. `marker` (a bullet or list number) is hung
+    /// in the indent to the left of the first line.
+    pub fn rich_text(&mut self, spans: &[Span], size: f32, indent: f32, marker: Option<&str>) {
+        let lines = self.wrap_spans(spans, size, self.content_width() - indent);
+        let height = Self::line_height(size);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.ensure_space(height);
+            let baseline = Mm(self.cursor - size * PT_TO_MM);
+            if let (0, Some(marker)) = (i, marker) {
+                let x = MARGIN_LEFT + indent - 1.5 - self.metrics.width_mm(marker, size);
+                self.layer().use_text(marker, size, Mm(x), baseline, &self.font);
+            }
+            for (x, weight, text) in line {
+                self.layer().use_text(text, size, Mm(MARGIN_LEFT + indent + x), baseline, self.font(weight));
+            }
+            self.cursor -= height;
+        }
+    }
+
+    /// Preformatted text in the monospace face on a shaded background.
+    /// Whitespace is kept; lines too wide for the page are broken by character.
+    pub fn code_block(&mut self, code: &str, size: f32, indent: f32) {
+        let padding = 2.0;
+        let height = Self::line_height(size);
+        let max_width = self.content_width() - indent - padding * 2.0;
+        let left = MARGIN_LEFT + indent;
+
+        let mut lines = Vec::new();
+        for line in code.trim_end_matches('\n').split('\n') {
+            lines.extend(self.metrics(Weight::Mono).break_chars(line.trim_end(), size, max_width));
+        }
+
+        self.ensure_space(height + padding);
+        self.shade(left, self.cursor - padding, self.cursor);
+        self.cursor -= padding;
+        for line in lines {
+            if self.cursor - height < MARGIN_BOTTOM {
+                self.new_page();
+            }
+            self.shade(left, self.cursor - height, self.cursor);
+            let baseline = Mm(self.cursor - size * PT_TO_MM);
+            self.layer().use_text(line, size, Mm(left + padding), baseline, self.font(Weight::Mono));
+            self.cursor -= height;
+        }
+        self.shade(left, self.cursor - padding, self.cursor);
+        self.cursor -= padding;
+    }
+
+    /// Light grey band from `left` to the right margin
+    fn shade(&self, left: f32, bottom: f32, top: f32) {
+        let layer = self.layer();
+        layer.set_fill_color(Color::Rgb(Rgb::new(0.94, 0.94, 0.94, None)));
+        layer.add_rect(Rect::new(Mm(left), Mm(bottom), Mm(PAGE_WIDTH - MARGIN_RIGHT), Mm(top)).with_mode(PaintMode::Fill));
+        layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    }
+
+    /// Horizontal rule at the cursor between two offsets from the left margin
+    pub fn rule(&mut self, from: f32, to: f32) {
+        self.hline(MARGIN_LEFT + from, MARGIN_LEFT + to, self.cursor);
+    }
+
+    fn hline(&self, x1: f32, x2: f32, y: f32) {
+        self.hline_weighted(x1, x2, y, 0.5);
+    }
+
+    fn hline_weighted(&self, x1: f32, x2: f32, y: f32, thickness: f32) {
+        let line = Line {
+            points: vec![(Point::new(Mm(x1), Mm(y)), false), (Point::new(Mm(x2), Mm(y)), false)],
+            is_closed: false,
+        };
+        self.layer().set_outline_thickness(thickness);
+        self.layer().add_line(line);
+    }
+
+    fn spans_width(&self, spans: &[Span], size: f32) -> f32 {
+        spans.iter().map(|span| self.metrics(span.weight).width_mm(&span.text, size)).sum()
+    }
+
+    /// Grid of wrapped cells. The first `header_rows` rows are set in bold and
+    /// repeated when the table continues onto a new page. Columns without an
+    /// entry in `aligns` are left-aligned.
+    pub fn table(&mut self, rows: &[Vec<Vec<Span>>], header_rows: usize, aligns: &[Align], size: f32, indent: f32) {
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let header_rows = header_rows.min(rows.len());
+        let rows: Vec<Vec<Vec<Span>>> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut row = row.clone();
+                row.resize(columns, Vec::new());
+                if i < header_rows {
+                    for span in row.iter_mut().flatten().filter(|span| span.weight == Weight::Regular) {
+                        span.weight = Weight::Bold;
+                    }
+                }
+                row
+            })
+            .collect();
+
+        let natural: Vec<f32> = (0..columns)
+            .map(|c| rows.iter().map(|row| self.spans_width(&row[c], size)).fold(0.0, f32::max) + TABLE_PADDING * 2.0)
+            .collect();
+        let widths = fit_columns(&natural, self.content_width() - indent);
+        let wrapped: Vec<Vec<Vec<RunLine>>> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| self.wrap_spans(cell, size, width - TABLE_PADDING * 2.0))
+                    .collect()
+            })
+            .collect();
+
+        let grid = TableGrid { widths, aligns, size, left: MARGIN_LEFT + indent };
+        for (i, cells) in wrapped.iter().enumerate() {
+            let header = i < header_rows;
+            if !header && self.cursor - grid.row_height(cells) < MARGIN_BOTTOM {
+                self.new_page();
+                for cells in &wrapped[..header_rows] {
+                    self.table_row(&grid, cells, true);
+                }
+            }
+            self.table_row(&grid, cells, header);
+        }
+    }
+
+    fn table_row(&mut self, grid: &TableGrid, cells: &[Vec<RunLine>], header: bool) {
+        let line_height = Self::line_height(grid.size);
+        let top = self.cursor;
+        let mut x = grid.left;
+        for (c, (lines, width)) in cells.iter().zip(&grid.widths).enumerate() {
+            let align = grid.aligns.get(c).copied().unwrap_or_default();
+            for (i, line) in lines.iter().enumerate() {
+                let line_width = line
+                    .last()
+                    .map(|(x, weight, text)| x + self.metrics(*weight).width_mm(text, grid.size))
+                    .unwrap_or(0.0);
+                let inner = width - TABLE_PADDING * 2.0;
+                let offset = match align {
+                    Align::Left => 0.0,
+                    Align::Center => (inner - line_width) / 2.0,
+                    Align::Right => inner - line_width,
+                };
+                let baseline = Mm(top - TABLE_PADDING - i as f32 * line_height - grid.size * PT_TO_MM);
+                for (run_x, weight, text) in line {
+                    self.layer().use_text(text, grid.size, Mm(x + TABLE_PADDING + offset + run_x), baseline, self.font(*weight));
+                }
+            }
+            x += width;
+        }
+        self.cursor -= grid.row_height(cells);
+        let right = grid.left + grid.widths.iter().sum::<f32>();
+        self.hline_weighted(grid.left, right, self.cursor, if header { 0.8 } else { 0.3 });
+    }
+
+    /// Section heading with a rule underneath, kept on the same page as the
+    /// first couple of lines that follow it
+    pub fn heading(&mut self, text: &str) {
+        self.ensure_space(Self::line_height(12.0) + Self::line_height(11.0) * 3.0);
+        self.text(text, 12.0, Weight::Bold);
+        self.hline(MARGIN_LEFT, PAGE_WIDTH - MARGIN_RIGHT, self.cursor + 1.0);
+        self.space(4.0);
+    }
+
+    /// Blank line to write on by hand, optionally captioned on the left
+    pub fn write_in_line(&mut self, caption: &str) {
+        let height = 9.0;
+        self.ensure_space(height);
+        self.cursor -= height;
+        let mut x = MARGIN_LEFT;
+        if !caption.is_empty() {
+            self.layer().use_text(caption, 10.0, Mm(x), Mm(self.cursor + 1.0), &self.font);
+            x += self.metrics.width_mm(caption, 10.0) + 3.0;
+        }
+        self.hline(x, PAGE_WIDTH - MARGIN_RIGHT, self.cursor);
+    }
+
+    /// Small bold caption followed by a wrapped value, like the labelled fields of the view page
+    pub fn field(&mut self, label: &str, value: &str) {
+        self.ensure_space(Self::line_height(8.0) + Self::line_height(11.0));
+        self.text(label, 8.0, Weight::Bold);
+        self.space(0.5);
+        self.text(value, 11.0, Weight::Regular);
+        self.space(5.0);
+    }
+
+    /// QR code in the top-right corner of the current page with `caption`
+    /// (usually the encoded link) underneath. Body text below the header is
+    /// pushed down so it can't run into the code.
+    pub fn qr_code(&mut self, payload: &str, caption: &str) -> PdfResult<()> {
+        let code = qrcode::QrCode::with_error_correction_level(payload, qrcode::EcLevel::M)?;
+        let modules = code.width();
+        let module = QR_SIZE / modules as f32;
+        let left = PAGE_WIDTH - MARGIN_RIGHT - QR_SIZE;
+        let top = PAGE_HEIGHT - MARGIN_TOP;
+
+        // One rectangle per horizontal run of dark modules keeps the page
+        // stream small; the slight overlap avoids hairline gaps between rows.
+        let colors = code.to_colors();
+        for (y, row) in colors.chunks(modules).enumerate() {
+            let mut x = 0;
+            while x < modules {
+                if row[x] != qrcode::Color::Dark {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < modules && row[x] == qrcode::Color::Dark {
+                    x += 1;
+                }
+                let row_top = top - y as f32 * module;
+                self.layer().add_rect(
+                    Rect::new(
+                        Mm(left + start as f32 * module),
+                        Mm(row_top - module - 0.02),
+                        Mm(left + x as f32 * module),
+                        Mm(row_top),
+                    )
+                    .with_mode(PaintMode::Fill),
+                );
+            }
+        }
+
+        let mut bottom = top - QR_SIZE;
+        if !caption.is_empty() {
+            bottom -= 3.0;
+            let x = PAGE_WIDTH - MARGIN_RIGHT - self.metrics.width_mm(caption, 7.0);
+            self.layer().use_text(caption, 7.0, Mm(x), Mm(bottom), &self.font);
+        }
+        self.cursor = self.cursor.min(bottom - 6.0);
+        Ok(())
+    }
+
+    fn draw_footers(&self) {
+        let total = self.layers.len();
+        for (i, layer) in self.layers.iter().enumerate() {
+            layer.use_text(&self.style.footer, 8.0, Mm(MARGIN_LEFT), Mm(FOOTER_Y), &self.font);
+            let page = format!("{} {} / {}", self.style.page_label, i + 1, total);
+            let x = PAGE_WIDTH - MARGIN_RIGHT - self.metrics.width_mm(&page, 8.0);
+            layer.use_text(page, 8.0, Mm(x), Mm(FOOTER_Y), &self.font);
+        }
+    }
+
+    pub fn finish(self) -> PdfResult<Vec<u8>> {
+        self.draw_footers();
+        let bytes = self.doc.save_to_bytes()?;
+        compress_font_streams(&bytes)
+    }
+}
+
+/// printpdf embeds fonts uncompressed, which costs ~1.4MB for the two DejaVu
+/// faces. FlateDecode on FontFile2 is standard, so compress them after saving.
+fn compress_font_streams(pdf: &[u8]) -> PdfResult<Vec<u8>> {
+    let mut doc = lopdf::Document::load_mem(pdf)?;
+    for object in doc.objects.values_mut() {
+        if let lopdf::Object::Stream(stream) = object {
+            if stream.dict.has(b"Length1") && !stream.dict.has(b"Filter") {
+                stream.compress()?;
+            }
+        }
+    }
+    let mut out = Vec::new();
+    doc.save_to(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_scales_with_size() {
+        let metrics = FontMetrics::regular().unwrap();
+        let small = metrics.width_mm("Puerto Morelos", 10.0);
+        let large = metrics.width_mm("Puerto Morelos", 20.0);
+        assert!(small > 0.0);
+        assert!((large - small * 2.0).abs() < 0.001);
+    }
+
+    fn style() -> PageStyle {
+        PageStyle {
+            header_title: "SOUTH CITY COMPUTER".to_string(),
+            header_subtitle: "Test".to_string(),
+            footer: "footer".to_string(),
+            page_label: "Page".to_string(),
+        }
+    }
+
+    fn line_text(line: &RunLine) -> String {
+        line.iter().map(|(_, _, text)| text.as_str()).collect::<Vec<_>>().join("|")
+    }
+
+    #[test]
+    fn test_wrap_respects_width_and_words() {
+        let layout = PdfLayout::new("Test", style()).unwrap();
+        let text = "Necesito reparación de mi computadora portátil, la pantalla parpadea y el teclado no responde después de la actualización.";
+        let lines = layout.wrap_spans(&[Span::new(text, Weight::Regular)], 11.0, 60.0);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert_eq!(line.len(), 1);
+            assert!(layout.metrics.width_mm(&line[0].2, 11.0) <= 60.0);
+        }
+        assert_eq!(lines.iter().map(line_text).collect::<Vec<_>>().join(" "), text);
+    }
+
+    #[test]
+    fn test_wrap_keeps_explicit_newlines() {
+        let layout = PdfLayout::new("Test", style()).unwrap();
+        let lines = layout.wrap_spans(&[Span::new("one\n\ntwo", Weight::Regular)], 10.0, 100.0);
+        assert_eq!(lines.iter().map(line_text).collect::<Vec<_>>(), vec!["one", "", "two"]);
+    }
+
+    #[test]
+    fn test_wrap_breaks_long_words() {
+        let layout = PdfLayout::new("Test", style()).unwrap();
+        let lines = layout.wrap_spans(&[Span::new("x".repeat(200), Weight::Regular)], 10.0, 30.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| layout.metrics.width_mm(&l[0].2, 10.0) <= 30.0));
+    }
+
+    #[test]
+    fn test_wrap_mixed_weights() {
+        let mut layout = PdfLayout::new("Test", style()).unwrap();
+        layout.enable_mono().unwrap();
+        let spans = [
+            Span::new("Run ", Weight::Regular),
+            Span::new("cargo build", Weight::Mono),
+            Span::new(", then ", Weight::Regular),
+            Span::new("deploy", Weight::Bold),
+            Span::new(".", Weight::Regular),
+        ];
+        let lines = layout.wrap_spans(&spans, 10.0, 170.0);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(line_text(&lines[0]), "Run|cargo build|, then|deploy|.");
+        let xs: Vec<f32> = lines[0].iter().map(|(x, _, _)| *x).collect();
+        assert!(xs.windows(2).all(|w| w[0] < w[1]));
+        // No space between "cargo build" and the comma that follows it
+        let run_end = xs[1] + layout.metrics(Weight::Mono).width_mm("cargo build", 10.0);
+        assert!((xs[2] - run_end).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_fit_columns() {
+        assert_eq!(fit_columns(&[10.0, 20.0], 100.0), vec![10.0, 20.0]);
+        assert_eq!(fit_columns(&[10.0, 100.0, 100.0], 110.0), vec![10.0, 50.0, 50.0]);
+    }
+
+    #[test]
+    fn test_long_text_adds_pages() {
+        let mut layout = PdfLayout::new("Test", style()).unwrap();
+        layout.text(&"Lorem ipsum dolor sit amet. ".repeat(400), 10.0, Weight::Regular);
+        assert!(layout.page_count() > 1);
+        assert!(layout.finish().unwrap().starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_qr_code_pushes_body_below_it() {
+        let mut layout = PdfLayout::new("Test", style()).unwrap();
+        let before = layout.cursor;
+        layout.qr_code("https://southcitycomputer.com/view/ab12cd34", "southcitycomputer.com/view/ab12cd34").unwrap();
+        assert!(layout.cursor < before);
+        assert!(layout.cursor < PAGE_HEIGHT - MARGIN_TOP - QR_SIZE);
+    }
+}
@@ -13,6 +13,7 @@ const DEFAULT_TARGET: &str = "southcitycomputer.com";
 struct PerformanceMetrics {
     dns_lookup_ms: f64,
     tcp_connect_ms: f64,
+    #[allow(dead_code)] // HTTP only for now
     tls_handshake_ms: f64,
     ttfb_ms: f64,  // Time to First Byte
     download_ms: f64,
@@ -25,6 +26,7 @@ struct PerformanceMetrics {
 struct TraceHop {
     hop_number: u8,
     ip_address: Option<String>,
+    #[allow(dead_code)] // reverse DNS not implemented yet
     hostname: Option<String>,
     rtt_ms: Vec<f64>,
     is_target: bool,
@@ -147,7 +149,7 @@ fn run_traceroute(target: &str) -> Result<NetworkDiagnostics, String> {
     let resolved_ip = resolve_hostname(target).ok().map(|(ip, _)| ip);
 
     let output = Command::new("traceroute")
-        .args(&["-n", "-q", "3", "-w", "2", target])
+        .args(["-n", "-q", "3", "-w", "2", target])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .output();
@@ -157,7 +159,7 @@ fn run_traceroute(target: &str) -> Result<NetworkDiagnostics, String> {
         Err(_) => {
             // Try tracert on Windows
             Command::new("tracert")
-                .args(&["-d", "-w", "2000", target])
+                .args(["-d", "-w", "2000", target])
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
                 .output()
@@ -0,0 +1,150 @@
+// Form submission rate limit
+// The contact and service inquiry endpoints write a CSV row per request, so a
+// script posting in a loop fills the disk and the admin page. Each client IP
+// gets rate_limit.form_posts_per_minute submissions per minute (fixed
+// window); past that the endpoints answer 429 with Retry-After.
+//
+// The client is the connecting address, unless that is one of
+// rate_limit.trusted_proxies (nginx on loopback by default): then it's the
+// right-most X-Forwarded-For entry that isn't a trusted proxy, the one the
+// proxy itself appended. Entries to the left of it come from the client and
+// are ignored.
+
+use actix_web::HttpRequest;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config;
+use crate::metrics::{in_network, parse_network};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Most client IPs tracked at once. Finished windows are dropped when the map
+/// fills; if it's still over half full after that, it's cleared, so each
+/// sweep is paid for by at least this many / 2 new clients.
+const MAX_TRACKED: usize = 10_000;
+
+pub struct Limiter {
+    limit: u32,
+    /// Window start and submissions so far, per client IP
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl Limiter {
+    pub fn new(limit: u32) -> Self {
+        Limiter { limit, windows: Mutex::new(HashMap::new()) }
+    }
+
+    /// Count a submission from `ip`. Err holds how long until the window
+    /// resets when the client is over the limit.
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= MAX_TRACKED && !windows.contains_key(&ip) {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+            if windows.len() > MAX_TRACKED / 2 {
+                log::warn!("Rate limit: {} clients in the last minute, forgetting them all", windows.len());
+                windows.clear();
+            }
+        }
+        let (start, count) = windows.entry(ip).or_insert((now, 0));
+        if now.duration_since(*start) >= WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return Err(WINDOW - now.duration_since(*start));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Shared limiter for the form endpoints
+pub fn forms() -> &'static Limiter {
+    static FORMS: OnceLock<Limiter> = OnceLock::new();
+    FORMS.get_or_init(|| Limiter::new(config::get().rate_limit.form_posts_per_minute))
+}
+
+fn trusted_proxies() -> &'static [(IpAddr, u8)] {
+    static PROXIES: OnceLock<Vec<(IpAddr, u8)>> = OnceLock::new();
+    PROXIES.get_or_init(|| config::get().rate_limit.trusted_proxies.iter().filter_map(|entry| parse_network(entry)).collect())
+}
+
+/// The client behind `peer`, believing `forwarded_for` only from a trusted
+/// proxy
+fn client_ip(peer: IpAddr, forwarded_for: Option<&str>, trusted: &[(IpAddr, u8)]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|&network| in_network(ip.to_canonical(), network));
+    if !is_trusted(peer) {
+        return peer;
+    }
+    let mut client = peer;
+    for entry in forwarded_for.unwrap_or("").rsplit(',') {
+        let Ok(ip) = entry.trim().parse::<IpAddr>() else { break };
+        client = ip.to_canonical();
+        if !is_trusted(client) {
+            break;
+        }
+    }
+    client
+}
+
+/// Client IP to count a request against
+pub fn client(req: &HttpRequest) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip().to_canonical();
+    let forwarded_for = req.headers().get("X-Forwarded-For").and_then(|v| v.to_str().ok());
+    Some(client_ip(peer, forwarded_for, trusted_proxies()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_limit_per_ip_and_window() {
+        let limiter = Limiter::new(2);
+        let start = Instant::now();
+        assert!(limiter.check(ip("10.0.0.1"), start).is_ok());
+        assert!(limiter.check(ip("10.0.0.1"), start).is_ok());
+        assert_eq!(limiter.check(ip("10.0.0.1"), start + Duration::from_secs(20)), Err(Duration::from_secs(40)));
+        assert!(limiter.check(ip("10.0.0.2"), start).is_ok());
+        assert!(limiter.check(ip("10.0.0.1"), start + WINDOW).is_ok());
+    }
+
+    #[test]
+    fn test_zero_means_unlimited() {
+        let limiter = Limiter::new(0);
+        let now = Instant::now();
+        assert!((0..100).all(|_| limiter.check(ip("10.0.0.1"), now).is_ok()));
+    }
+
+    #[test]
+    fn test_tracked_clients_capped() {
+        let limiter = Limiter::new(1);
+        let now = Instant::now();
+        for n in 0..MAX_TRACKED as u32 + 10 {
+            assert!(limiter.check(IpAddr::from((0x0a00_0000 + n).to_be_bytes()), now).is_ok());
+        }
+        assert!(limiter.windows.lock().unwrap().len() <= MAX_TRACKED);
+    }
+
+    #[test]
+    fn test_forwarded_for_only_from_trusted_proxies() {
+        let trusted = [parse_network("127.0.0.1").unwrap()];
+        // Direct clients can't pick their own address
+        assert_eq!(client_ip(ip("203.0.113.9"), Some("198.51.100.1"), &trusted), ip("203.0.113.9"));
+        // Behind nginx: the entry nginx appended, not what the client sent
+        assert_eq!(client_ip(ip("127.0.0.1"), Some("198.51.100.1, 203.0.113.9"), &trusted), ip("203.0.113.9"));
+        assert_eq!(client_ip(ip("127.0.0.1"), Some("203.0.113.9, 127.0.0.1"), &trusted), ip("203.0.113.9"));
+        assert_eq!(client_ip(ip("127.0.0.1"), Some("junk, 203.0.113.9"), &trusted), ip("203.0.113.9"));
+        assert_eq!(client_ip(ip("127.0.0.1"), None, &trusted), ip("127.0.0.1"));
+    }
+}
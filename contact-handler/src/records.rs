@@ -0,0 +1,682 @@
+// CSV record storage
+// Every store keeps a list of schema versions, newest last. New files are
+// written with the newest header; existing files keep whatever header they were
+// created with and new rows are written in the newest layout (a file with an
+// unrecognised header isn't written to until it's migrated). Readers map columns by header
+// name, so older files never misalign, and `scc-server migrate` rewrites them
+// to the current schema after taking a timestamped backup. The files live in
+// storage.data_dir (see config.rs).
+
+use chrono::Local;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::analytics::{Counts, ViewKey};
+use crate::billing::{Currency, Invoice, Quote};
+use crate::{config, metrics, HoneypotRecord, ServiceInquiryRecord, Submission};
+
+pub struct CsvSchema {
+    pub name: &'static str,
+    pub file: &'static str,
+    /// Column lists per version, oldest first
+    pub versions: &'static [&'static [&'static str]],
+}
+
+impl CsvSchema {
+    /// Where the store lives: `file` inside the data directory
+    pub fn path(&self) -> PathBuf {
+        config::get().storage.path(self.file)
+    }
+
+    pub fn current(&self) -> &'static [&'static str] {
+        self.versions[self.versions.len() - 1]
+    }
+
+    pub fn current_version(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// 1-based version number whose columns exactly match `header`
+    pub fn version_of(&self, header: &[String]) -> Option<usize> {
+        self.versions
+            .iter()
+            .position(|cols| cols.len() == header.len() && cols.iter().zip(header).all(|(a, b)| a == b))
+            .map(|i| i + 1)
+    }
+}
+
+pub const CONTACTS: CsvSchema = CsvSchema {
+    name: "contacts",
+    file: "contacts.csv",
+    versions: &[&["id", "timestamp", "name", "email", "phone", "message"]],
+};
+
+pub const SERVICE_INQUIRIES: CsvSchema = CsvSchema {
+    name: "service_inquiries",
+    file: "service_inquiries.csv",
+    versions: &[&["id", "timestamp", "service_type", "name", "email", "phone", "details", "answers"]],
+};
+
+pub const HONEYPOT_ATTEMPTS: CsvSchema = CsvSchema {
+    name: "honeypot_attempts",
+    file: "honeypot_attempts.csv",
+    versions: &[
+        // v1: browser fingerprint columns from the JS honeypot pages
+        &[
+            "timestamp", "source", "username", "password", "ip", "user_agent", "screen", "timezone",
+            "language", "platform", "cookies", "dnt", "webgl", "canvas_hash", "touch",
+        ],
+        // v2: plugins plus request details from the server-side decoys
+        &[
+            "timestamp", "source", "username", "password", "ip", "user_agent", "screen", "timezone",
+            "language", "platform", "cookies", "dnt", "webgl", "canvas_hash", "touch", "plugins",
+            "path", "headers", "body",
+        ],
+    ],
+};
+
+pub const QUOTES: CsvSchema = CsvSchema {
+    name: "quotes",
+    file: "quotes.csv",
+    versions: &[&[
+        "id", "created", "inquiry_id", "service_type", "name", "email", "phone", "currency", "tax_rate",
+        "valid_until", "items", "notes",
+    ]],
+};
+
+pub const INVOICES: CsvSchema = CsvSchema {
+    name: "invoices",
+    file: "invoices.csv",
+    versions: &[&["number", "issued", "due", "quote_id"]],
+};
+
+/// Page view counts, appended as increments (see analytics.rs)
+pub const PAGE_VIEWS: CsvSchema = CsvSchema {
+    name: "page_views",
+    file: "page_views.csv",
+    versions: &[&["day", "path", "referrer", "lang", "views", "visitors"]],
+};
+
+pub const ALL_SCHEMAS: &[&CsvSchema] = &[&CONTACTS, &SERVICE_INQUIRIES, &HONEYPOT_ATTEMPTS, &QUOTES, &INVOICES, &PAGE_VIEWS];
+
+/// Backslash-escape separators, and backslashes so a field ending in one
+/// can't swallow the next separator
+pub fn escape_csv_field(field: &str) -> String {
+    field.replace('\\', "\\\\").replace(',', "\\,").replace('\n', " ").replace('\r', "")
+}
+
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(&next) = chars.peek() {
+                if next == ',' || next == '\\' {
+                    current.push(next);
+                    chars.next();
+                    continue;
+                }
+            }
+            current.push(c);
+        } else if c == ',' {
+            fields.push(current.clone());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// A parsed CSV file whose rows are accessed by column name.
+pub struct CsvTable {
+    pub header: Vec<String>,
+    /// Header used for rows longer than `header`: rows appended with a newer
+    /// schema to a file that still carries an older header.
+    extended_header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub struct CsvRow<'a> {
+    header: &'a [String],
+    fields: &'a [String],
+}
+
+impl CsvRow<'_> {
+    pub fn get(&self, column: &str) -> String {
+        self.header
+            .iter()
+            .position(|c| c == column)
+            .and_then(|i| self.fields.get(i))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl CsvTable {
+    pub fn parse(schema: &CsvSchema, content: &str) -> Self {
+        let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+        let header: Vec<String> = lines
+            .next()
+            .map(|h| parse_csv_line(h).into_iter().map(|c| c.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        let current = schema.current();
+        let is_prefix = header.len() < current.len() && header.iter().zip(current).all(|(a, b)| a == b);
+        let extended_header = if is_prefix {
+            current.iter().map(|c| c.to_string()).collect()
+        } else {
+            header.clone()
+        };
+
+        CsvTable {
+            header,
+            extended_header,
+            rows: lines.map(parse_csv_line).collect(),
+        }
+    }
+
+    /// Read a store from disk; a missing file is an empty table.
+    pub fn read(schema: &CsvSchema) -> Self {
+        let content = fs::read_to_string(schema.path()).unwrap_or_default();
+        Self::parse(schema, &content)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = CsvRow<'_>> {
+        self.rows.iter().map(move |fields| CsvRow {
+            header: if fields.len() > self.header.len() { &self.extended_header } else { &self.header },
+            fields,
+        })
+    }
+}
+
+fn read_header(path: &Path) -> io::Result<Option<Vec<String>>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line)?;
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(parse_csv_line(line.trim_end()).into_iter().map(|c| c.trim().to_string()).collect()))
+}
+
+/// Append one record in the current schema's layout. A file whose header is
+/// an older version of the schema gets longer rows, which CsvTable reads
+/// through the current header; a file with any other header is left alone
+/// and the write refused until `scc-server migrate` has upgraded it.
+pub fn append_record(schema: &CsvSchema, values: &[(&str, &str)]) -> io::Result<()> {
+    let result = write_record(schema, &schema.path(), values);
+    metrics::record_write(schema.name, result.is_ok());
+    result
+}
+
+fn write_record(schema: &CsvSchema, path: &Path, values: &[(&str, &str)]) -> io::Result<()> {
+    let current = schema.current();
+    let existing = read_header(path)?;
+    let header: Vec<String> = match existing.clone() {
+        None => current.iter().map(|c| c.to_string()).collect(),
+        // Current, or current plus columns kept by a migration
+        Some(header) if header.len() >= current.len() && current.iter().zip(&header).all(|(a, b)| a == b) => header,
+        // An older version the current schema extends
+        Some(header) if header.iter().zip(current).all(|(a, b)| a == b) => current.iter().map(|c| c.to_string()).collect(),
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: unrecognised {} header; run `scc-server migrate`", path.display(), schema.name),
+            ))
+        }
+    };
+
+    let line = header
+        .iter()
+        .map(|col| {
+            values
+                .iter()
+                .find(|(name, _)| name == col)
+                .map(|(_, value)| escape_csv_field(value))
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if existing.is_none() {
+        writeln!(file, "{}", header.join(","))?;
+    }
+    writeln!(file, "{}", line)
+}
+
+pub fn append_contact(sub: &Submission) -> io::Result<()> {
+    append_record(
+        &CONTACTS,
+        &[
+            ("id", &sub.id),
+            ("timestamp", &sub.timestamp),
+            ("name", &sub.name),
+            ("email", &sub.email),
+            ("phone", &sub.phone),
+            ("message", &sub.message),
+        ],
+    )
+}
+
+pub fn append_service_inquiry(inquiry: &ServiceInquiryRecord) -> io::Result<()> {
+    let answers = serde_json::to_string(&inquiry.answers).unwrap_or_else(|_| "{}".to_string());
+    append_record(
+        &SERVICE_INQUIRIES,
+        &[
+            ("id", &inquiry.id),
+            ("timestamp", &inquiry.timestamp),
+            ("service_type", &inquiry.service_type),
+            ("name", &inquiry.name),
+            ("email", &inquiry.email),
+            ("phone", &inquiry.phone),
+            ("details", &inquiry.details),
+            ("answers", &answers),
+        ],
+    )
+}
+
+pub fn append_honeypot_attempt(record: &HoneypotRecord) -> io::Result<()> {
+    metrics::honeypot_hit(&record.source);
+    append_record(
+        &HONEYPOT_ATTEMPTS,
+        &[
+            ("timestamp", &record.timestamp),
+            ("source", &record.source),
+            ("username", &record.username),
+            ("password", &record.password),
+            ("ip", &record.ip),
+            ("user_agent", &record.user_agent),
+            ("screen", &record.screen),
+            ("timezone", &record.timezone),
+            ("language", &record.language),
+            ("platform", &record.platform),
+            ("cookies", &record.cookies),
+            ("dnt", &record.dnt),
+            ("webgl", &record.webgl),
+            ("canvas_hash", &record.canvas_hash),
+            ("touch", &record.touch),
+            ("plugins", &record.plugins),
+            ("path", &record.path),
+            ("headers", &record.headers),
+            ("body", &record.body),
+        ],
+    )
+}
+
+pub fn append_quote(quote: &Quote) -> io::Result<()> {
+    let items = serde_json::to_string(&quote.items).unwrap_or_else(|_| "[]".to_string());
+    let tax_rate = quote.tax_rate.to_string();
+    append_record(
+        &QUOTES,
+        &[
+            ("id", &quote.id),
+            ("created", &quote.created),
+            ("inquiry_id", &quote.inquiry_id),
+            ("service_type", &quote.service_type),
+            ("name", &quote.name),
+            ("email", &quote.email),
+            ("phone", &quote.phone),
+            ("currency", quote.currency.code()),
+            ("tax_rate", &tax_rate),
+            ("valid_until", &quote.valid_until),
+            ("items", &items),
+            ("notes", &quote.notes),
+        ],
+    )
+}
+
+pub fn append_invoice(invoice: &Invoice) -> io::Result<()> {
+    append_record(
+        &INVOICES,
+        &[
+            ("number", &invoice.number.to_string()),
+            ("issued", &invoice.issued),
+            ("due", &invoice.due),
+            ("quote_id", &invoice.quote_id),
+        ],
+    )
+}
+
+pub fn append_page_views(key: &ViewKey, counts: Counts) -> io::Result<()> {
+    append_record(
+        &PAGE_VIEWS,
+        &[
+            ("day", &key.day),
+            ("path", &key.path),
+            ("referrer", &key.referrer),
+            ("lang", &key.lang),
+            ("views", &counts.views.to_string()),
+            ("visitors", &counts.visitors.to_string()),
+        ],
+    )
+}
+
+fn contact_from_row(row: &CsvRow) -> Submission {
+    Submission {
+        id: row.get("id"),
+        timestamp: row.get("timestamp"),
+        name: row.get("name"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        message: row.get("message"),
+    }
+}
+
+fn service_inquiry_from_row(row: &CsvRow) -> ServiceInquiryRecord {
+    let answers = serde_json::from_str(&row.get("answers"))
+        .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+    ServiceInquiryRecord {
+        id: row.get("id"),
+        timestamp: row.get("timestamp"),
+        service_type: row.get("service_type"),
+        name: row.get("name"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        details: row.get("details"),
+        answers,
+    }
+}
+
+fn honeypot_from_row(row: &CsvRow) -> HoneypotRecord {
+    HoneypotRecord {
+        timestamp: row.get("timestamp"),
+        source: row.get("source"),
+        username: row.get("username"),
+        password: row.get("password"),
+        ip: row.get("ip"),
+        user_agent: row.get("user_agent"),
+        screen: row.get("screen"),
+        timezone: row.get("timezone"),
+        language: row.get("language"),
+        platform: row.get("platform"),
+        cookies: row.get("cookies"),
+        dnt: row.get("dnt"),
+        webgl: row.get("webgl"),
+        canvas_hash: row.get("canvas_hash"),
+        touch: row.get("touch"),
+        plugins: row.get("plugins"),
+        path: row.get("path"),
+        headers: row.get("headers"),
+        body: row.get("body"),
+    }
+}
+
+fn quote_from_row(row: &CsvRow) -> Quote {
+    Quote {
+        id: row.get("id"),
+        created: row.get("created"),
+        inquiry_id: row.get("inquiry_id"),
+        service_type: row.get("service_type"),
+        name: row.get("name"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        currency: Currency::parse(&row.get("currency")),
+        tax_rate: row.get("tax_rate").parse().unwrap_or(0.0),
+        valid_until: row.get("valid_until"),
+        items: serde_json::from_str(&row.get("items")).unwrap_or_default(),
+        notes: row.get("notes"),
+    }
+}
+
+pub fn load_contacts() -> Vec<Submission> {
+    CsvTable::read(&CONTACTS).rows().map(|r| contact_from_row(&r)).collect()
+}
+
+pub fn load_service_inquiries() -> Vec<ServiceInquiryRecord> {
+    CsvTable::read(&SERVICE_INQUIRIES).rows().map(|r| service_inquiry_from_row(&r)).collect()
+}
+
+pub fn load_honeypot_attempts() -> Vec<HoneypotRecord> {
+    CsvTable::read(&HONEYPOT_ATTEMPTS).rows().map(|r| honeypot_from_row(&r)).collect()
+}
+
+pub fn load_quotes() -> Vec<Quote> {
+    CsvTable::read(&QUOTES).rows().map(|r| quote_from_row(&r)).collect()
+}
+
+/// Rows whose counts aren't numbers are dropped
+pub fn load_page_views() -> Vec<(ViewKey, Counts)> {
+    CsvTable::read(&PAGE_VIEWS)
+        .rows()
+        .filter_map(|r| {
+            let counts = Counts { views: r.get("views").parse().ok()?, visitors: r.get("visitors").parse().ok()? };
+            Some((ViewKey { day: r.get("day"), path: r.get("path"), referrer: r.get("referrer"), lang: r.get("lang") }, counts))
+        })
+        .collect()
+}
+
+/// Invoices with an unreadable number are skipped rather than renumbered
+pub fn load_invoices() -> Vec<Invoice> {
+    CsvTable::read(&INVOICES)
+        .rows()
+        .filter_map(|r| {
+            Some(Invoice {
+                number: r.get("number").parse().ok()?,
+                issued: r.get("issued"),
+                due: r.get("due"),
+                quote_id: r.get("quote_id"),
+            })
+        })
+        .collect()
+}
+
+pub enum MigrationOutcome {
+    Missing,
+    UpToDate,
+    Migrated {
+        from: Option<usize>,
+        rows: usize,
+        backup: String,
+    },
+}
+
+/// Column layout for a migrated file: the current schema, followed by any
+/// columns the old file had that the schema doesn't know about.
+fn migrated_columns(schema: &CsvSchema, header: &[String]) -> Vec<String> {
+    let mut columns: Vec<String> = schema.current().iter().map(|c| c.to_string()).collect();
+    for col in header {
+        if !col.is_empty() && !columns.contains(col) {
+            columns.push(col.clone());
+        }
+    }
+    columns
+}
+
+fn render_migrated(schema: &CsvSchema, table: &CsvTable) -> String {
+    let columns = migrated_columns(schema, &table.header);
+    let mut out = columns.join(",");
+    out.push('\n');
+    for row in table.rows() {
+        let line = columns
+            .iter()
+            .map(|col| escape_csv_field(&row.get(col)))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Rewrite a store in place with the current schema, keeping a backup copy.
+pub fn migrate(schema: &CsvSchema) -> io::Result<MigrationOutcome> {
+    let path = &schema.path();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(MigrationOutcome::Missing),
+        Err(e) => return Err(e),
+    };
+
+    let table = CsvTable::parse(schema, &content);
+    let from = schema.version_of(&table.header);
+    let has_long_rows = table.rows.iter().any(|r| r.len() > table.header.len());
+    if from == Some(schema.current_version()) && !has_long_rows {
+        return Ok(MigrationOutcome::UpToDate);
+    }
+
+    let backup = format!("{}.bak-{}", path.display(), Local::now().format("%Y%m%d-%H%M%S"));
+    fs::copy(path, &backup)?;
+
+    let tmp = format!("{}.tmp", path.display());
+    fs::write(&tmp, render_migrated(schema, &table))?;
+    fs::rename(&tmp, path)?;
+
+    Ok(MigrationOutcome::Migrated {
+        from,
+        rows: table.rows.len(),
+        backup,
+    })
+}
+
+/// `scc-server migrate`: upgrade every store in the data directory.
+pub fn run_migrate() -> io::Result<()> {
+    for schema in ALL_SCHEMAS {
+        let file = schema.path();
+        let file = file.display();
+        match migrate(schema)? {
+            MigrationOutcome::Missing => println!("{}: not found, skipping", file),
+            MigrationOutcome::UpToDate => {
+                println!("{}: already at v{}", file, schema.current_version())
+            }
+            MigrationOutcome::Migrated { from, rows, backup } => println!(
+                "{}: migrated {} rows from {} to v{} (backup: {})",
+                file,
+                rows,
+                from.map(|v| format!("v{}", v)).unwrap_or_else(|| "unknown schema".to_string()),
+                schema.current_version(),
+                backup
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Stores whose file is on an older (or unknown) schema
+pub fn outdated_schemas() -> Vec<&'static CsvSchema> {
+    ALL_SCHEMAS
+        .iter()
+        .copied()
+        .filter(|schema| match read_header(&schema.path()) {
+            Ok(Some(header)) => schema.version_of(&header) != Some(schema.current_version()),
+            _ => false,
+        })
+        .collect()
+}
+
+/// Print a warning at startup for any store still on an older schema.
+pub fn check_schemas() {
+    for schema in outdated_schemas() {
+        eprintln!(
+            "Warning: {} uses an older {} schema; run `scc-server migrate` to upgrade it",
+            schema.path().display(),
+            schema.name
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_line_escaped_comma() {
+        assert_eq!(parse_csv_line("a,b\\,c,d"), vec!["a", "b,c", "d"]);
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let fields = ["ends in \\", "a\\,b", "c:\\temp, d", ""];
+        let line = fields.iter().map(|f| escape_csv_field(f)).collect::<Vec<_>>().join(",");
+        assert_eq!(parse_csv_line(&line), fields);
+    }
+
+    #[test]
+    fn test_version_of() {
+        let v1: Vec<String> = HONEYPOT_ATTEMPTS.versions[0].iter().map(|c| c.to_string()).collect();
+        assert_eq!(HONEYPOT_ATTEMPTS.version_of(&v1), Some(1));
+        assert_eq!(HONEYPOT_ATTEMPTS.version_of(&["ip".to_string()]), None);
+    }
+
+    #[test]
+    fn test_rows_map_by_header_name() {
+        let table = CsvTable::parse(&CONTACTS, "email,id,name\nbob@example.com,abc,Bob\n");
+        let row = table.rows().next().unwrap();
+        assert_eq!(row.get("id"), "abc");
+        assert_eq!(row.get("email"), "bob@example.com");
+        assert_eq!(row.get("phone"), "");
+    }
+
+    #[test]
+    fn test_long_rows_under_old_header_use_current_schema() {
+        let content = format!(
+            "{}\n{}\n",
+            HONEYPOT_ATTEMPTS.versions[0].join(","),
+            "t,decoy-env,,,1.2.3.4,curl,,,,,,,,,,,GET /.env,host: x,"
+        );
+        let table = CsvTable::parse(&HONEYPOT_ATTEMPTS, &content);
+        let row = table.rows().next().unwrap();
+        assert_eq!(row.get("ip"), "1.2.3.4");
+        assert_eq!(row.get("path"), "GET /.env");
+    }
+
+    #[test]
+    fn test_append_to_v1_file_keeps_new_columns() {
+        let path = std::env::temp_dir().join(format!("scc-records-v1-{}.csv", std::process::id()));
+        fs::write(&path, format!("{}\n", HONEYPOT_ATTEMPTS.versions[0].join(","))).unwrap();
+        write_record(&HONEYPOT_ATTEMPTS, &path, &[("source", "decoy-env"), ("ip", "1.2.3.4"), ("path", "GET /.env"), ("body", "x")]).unwrap();
+
+        let table = CsvTable::parse(&HONEYPOT_ATTEMPTS, &fs::read_to_string(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(table.header.len(), HONEYPOT_ATTEMPTS.versions[0].len());
+        let row = table.rows().next().unwrap();
+        assert_eq!(row.get("ip"), "1.2.3.4");
+        assert_eq!(row.get("path"), "GET /.env");
+        assert_eq!(row.get("body"), "x");
+    }
+
+    #[test]
+    fn test_append_refused_for_unknown_header() {
+        let path = std::env::temp_dir().join(format!("scc-records-unknown-{}.csv", std::process::id()));
+        fs::write(&path, "email,id,name\nbob@example.com,abc,Bob\n").unwrap();
+        assert!(write_record(&CONTACTS, &path, &[("id", "def")]).is_err());
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(content, "email,id,name\nbob@example.com,abc,Bob\n");
+    }
+
+    #[test]
+    fn test_render_migrated_keeps_unknown_columns() {
+        let table = CsvTable::parse(&CONTACTS, "id,name,service\nabc,Bob,repair\n");
+        let out = render_migrated(&CONTACTS, &table);
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("id,timestamp,name,email,phone,message,service"));
+        assert_eq!(lines.next(), Some("abc,,Bob,,,,repair"));
+    }
+
+    #[test]
+    fn test_quote_row_round_trip() {
+        let items = r#"[{"kind":"labor"\,"description":"Diagnóstico\, limpieza"\,"quantity":1.5\,"unit_price":450.0}]"#;
+        let csv = format!(
+            "{}\nq1,2026-01-01 10:00:00,inq1,computer-repair,Ana,ana@example.com,,USD,8,2026-01-16,{},Pay on pickup\n",
+            QUOTES.current().join(","),
+            items
+        );
+        let table = CsvTable::parse(&QUOTES, &csv);
+        let quote = quote_from_row(&table.rows().next().unwrap());
+        assert_eq!(quote.currency, Currency::Usd);
+        assert_eq!(quote.tax_rate, 8.0);
+        assert_eq!(quote.items.len(), 1);
+        assert_eq!(quote.items[0].description, "Diagnóstico, limpieza");
+        assert_eq!(quote.notes, "Pay on pickup");
+    }
+}
@@ -0,0 +1,158 @@
+// Document QR codes and the shop-side scan page
+// Submission and intake PDFs carry a QR code of the record's public view URL;
+// quotes and invoices carry their bare reference. At the counter, a barcode
+// scanner (which types what it reads, then Enter) into /contact-admin/scan
+// jumps straight to the record, whichever of the two forms was scanned.
+// Documents link to PUBLIC_URL (see public_url()).
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use crate::billing::{admin_page, unauthorized};
+use crate::{check_auth, find_service_inquiry_by_id, find_submission_by_id, html_escape, lang, public_url, records};
+
+/// Public view URL for a contact or service inquiry, and the shorter form
+/// printed under the QR code
+pub fn view_link(id: &str) -> (String, String) {
+    let url = format!("{}/view/{}", public_url(), id);
+    let caption = url.split_once("://").map(|(_, rest)| rest.to_string()).unwrap_or_else(|| url.clone());
+    (url, caption)
+}
+
+/// Record id from whatever the scanner read: a view URL (with or without
+/// "/pdf", a query or a fragment) or a bare reference
+pub fn scanned_reference(code: &str) -> Option<String> {
+    let code = code.trim();
+    let reference = match code.split_once("/view/") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next().unwrap_or(""),
+        None => code,
+    };
+    let valid = !reference.is_empty() && reference.len() <= 64 && reference.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    valid.then(|| reference.to_string())
+}
+
+/// Invoice number from a label like "SCC-00042"
+fn invoice_number(reference: &str) -> Option<u32> {
+    let (prefix, number) = reference.split_once('-')?;
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// Admin location for a scanned reference, if it matches a record
+fn scan_target(reference: &str) -> Option<String> {
+    if let Some(number) = invoice_number(reference) {
+        return records::load_invoices()
+            .iter()
+            .any(|inv| inv.number == number)
+            .then(|| format!("/contact-admin/invoices/{}/pdf", number));
+    }
+    if find_submission_by_id(reference).is_some() || find_service_inquiry_by_id(reference).is_some() {
+        return Some(format!("/view/{}", reference));
+    }
+    records::load_quotes()
+        .iter()
+        .any(|quote| quote.id == reference)
+        .then(|| format!("/contact-admin/quotes/{}/pdf", reference))
+}
+
+#[derive(Deserialize)]
+struct ScanQuery {
+    code: Option<String>,
+}
+
+async fn scan_page(req: HttpRequest, query: web::Query<ScanQuery>) -> HttpResponse {
+    if !check_auth(&req) {
+        return unauthorized();
+    }
+    let lang = lang::negotiate(&req);
+    let t = |en, es| lang.pick(en, es);
+
+    let code = query.code.as_deref().unwrap_or("").trim();
+    let message = if code.is_empty() {
+        String::new()
+    } else {
+        match scanned_reference(code).and_then(|reference| scan_target(&reference)) {
+            Some(location) => {
+                return HttpResponse::SeeOther().insert_header(("Location", location)).finish();
+            }
+            None => format!("{} <strong>{}</strong>.", t("No record matches", "Ningún registro coincide con"), html_escape(code)),
+        }
+    };
+
+    admin_page(lang, format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{t_title} - South City Computer</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; background: #1a1a1a; color: #fff; min-height: 100vh; padding: 20px; }}
+        .container {{ max-width: 600px; margin: 60px auto; }}
+        h1 {{ margin-bottom: 24px; background: linear-gradient(135deg, #e91e8c, #00bcd4); -webkit-background-clip: text; -webkit-text-fill-color: transparent; background-clip: text; }}
+        a {{ color: #00bcd4; text-decoration: none; }}
+        p {{ color: #888; margin-bottom: 16px; }}
+        input {{ width: 100%; padding: 16px; font-size: 1.25rem; border-radius: 8px; border: 1px solid #555; background: #333; color: #fff; }}
+        .message {{ color: #ff6b6b; margin-top: 16px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <a href="/contact-admin">&larr; {t_back}</a>
+        <h1>{t_title}</h1>
+        <p>{t_help}</p>
+        <form method="get" action="/contact-admin/scan">
+            <input name="code" autofocus autocomplete="off" placeholder="{t_placeholder}">
+        </form>
+        <div class="message">{message}</div>
+    </div>
+</body>
+</html>"#,
+        lang = lang.code(),
+        message = message,
+        t_title = t("Scan Document", "Escanear documento"),
+        t_back = t("Back to Admin", "Volver al panel"),
+        t_help = t(
+            "Scan the QR code on a submission, intake form, quote or invoice, or type its reference and press Enter.",
+            "Escanea el código QR de un envío, formulario de ingreso, cotización o factura, o escribe su referencia y presiona Enter."
+        ),
+        t_placeholder = t("Reference or link", "Referencia o enlace"),
+    ))
+}
+
+pub fn configure_scan(cfg: &mut web::ServiceConfig) {
+    cfg.route("/contact-admin/scan", web::get().to(scan_page));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanned_reference() {
+        assert_eq!(scanned_reference("https://southcitycomputer.com/view/ab12cd34").as_deref(), Some("ab12cd34"));
+        assert_eq!(scanned_reference(" http://localhost:9000/view/ab12cd34/pdf?x=1\n").as_deref(), Some("ab12cd34"));
+        assert_eq!(scanned_reference("SCC-00042").as_deref(), Some("SCC-00042"));
+        assert_eq!(scanned_reference("ab12cd34").as_deref(), Some("ab12cd34"));
+        assert_eq!(scanned_reference("../../etc/passwd"), None);
+        assert_eq!(scanned_reference(""), None);
+    }
+
+    #[test]
+    fn test_invoice_number() {
+        assert_eq!(invoice_number("SCC-00042"), Some(42));
+        assert_eq!(invoice_number("ab12cd34"), None);
+        assert_eq!(invoice_number("-12"), None);
+    }
+
+    #[test]
+    fn test_view_link_caption_drops_scheme() {
+        let (url, caption) = view_link("ab12cd34");
+        assert!(url.ends_with("/view/ab12cd34"));
+        assert!(url.starts_with("http"));
+        assert!(!caption.contains("://"));
+    }
+}
@@ -0,0 +1,251 @@
+// Security response headers
+// nginx used to add X-Frame-Options, nosniff and Referrer-Policy; the binary
+// now sends its own, so running standalone, in Docker or in the desktop app is
+// covered too. Each route group gets a Content-Security-Policy fitted to what
+// its pages load. The generated admin and view pages carry a fresh nonce on
+// every response, and their <script> tags use it (see nonce()), so no other
+// inline script runs there. The static site keeps 'unsafe-inline' for the
+// onclick handlers in the embedded pages. Decoys are left alone, so they
+// look like the servers they imitate.
+//
+// Configuration ([security_headers] in config.rs):
+//   groups       - Route groups to cover (site, admin, view, api, decoy); empty
+//                  sends none, e.g. behind a proxy that adds its own. Defaults
+//                  to all but decoy.
+//   hsts_max_age - Strict-Transport-Security max-age in seconds; 0 drops the
+//                  header. Defaults to one year.
+//   csp.<group>  - Replace a group's Content-Security-Policy; "{nonce}" is
+//                  filled in per response, "off" drops the header.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, HttpRequest};
+use std::sync::OnceLock;
+
+use crate::{config, decoys};
+
+const PERMISSIONS_POLICY: &str = "camera=(), microphone=(), geolocation=(), payment=(), usb=()";
+
+/// The embedded site: main.js plus inline onclick handlers, Google Fonts on the
+/// honeypot login page, images from anywhere a page links them
+const SITE_CSP: &str = "default-src 'self'; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline' https://fonts.googleapis.com; \
+    font-src 'self' https://fonts.gstatic.com; img-src 'self' data: https:; media-src 'self'; connect-src 'self'; \
+    object-src 'none'; frame-ancestors 'self'; base-uri 'self'; form-action 'self'";
+
+/// Admin pages: nonce'd scripts, the Leaflet attack map with its CartoDB tiles
+/// and ipapi.co lookups
+const ADMIN_CSP: &str = "default-src 'self'; script-src 'nonce-{nonce}'; style-src 'self' 'unsafe-inline' https://unpkg.com; \
+    img-src 'self' data: https://*.basemaps.cartocdn.com; connect-src 'self' https://ipapi.co; \
+    object-src 'none'; frame-ancestors 'none'; base-uri 'none'; form-action 'self'";
+
+const VIEW_CSP: &str = "default-src 'self'; script-src 'nonce-{nonce}'; style-src 'self' 'unsafe-inline'; img-src 'self' data:; \
+    object-src 'none'; frame-ancestors 'none'; base-uri 'none'; form-action 'self'";
+
+const API_CSP: &str = "default-src 'none'; frame-ancestors 'none'";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteGroup {
+    Site,
+    Admin,
+    View,
+    Api,
+    Decoy,
+}
+
+/// Every route group; config.rs reads a CSP_<GROUP> override for each
+pub const GROUPS: [RouteGroup; 5] = [RouteGroup::Site, RouteGroup::Admin, RouteGroup::View, RouteGroup::Api, RouteGroup::Decoy];
+
+impl RouteGroup {
+    pub fn of(path: &str) -> RouteGroup {
+        if path == "/contact-admin" || path.starts_with("/contact-admin/") {
+            RouteGroup::Admin
+        } else if path.starts_with("/view/") {
+            RouteGroup::View
+        } else if path.starts_with("/api/") || path == "/health" || path.starts_with("/health/") {
+            RouteGroup::Api
+        } else if decoys::decoy_paths().any(|decoy| decoy == path) {
+            RouteGroup::Decoy
+        } else {
+            RouteGroup::Site
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RouteGroup::Site => "site",
+            RouteGroup::Admin => "admin",
+            RouteGroup::View => "view",
+            RouteGroup::Api => "api",
+            RouteGroup::Decoy => "decoy",
+        }
+    }
+
+    fn default_csp(self) -> Option<&'static str> {
+        match self {
+            RouteGroup::Site => Some(SITE_CSP),
+            RouteGroup::Admin => Some(ADMIN_CSP),
+            RouteGroup::View => Some(VIEW_CSP),
+            RouteGroup::Api => Some(API_CSP),
+            RouteGroup::Decoy => None,
+        }
+    }
+
+    /// Pages that can be framed by, and send referrers to, other sites
+    fn public(self) -> bool {
+        self == RouteGroup::Site
+    }
+}
+
+struct Config {
+    groups: Vec<RouteGroup>,
+    hsts: Option<String>,
+    /// Content-Security-Policy per group, in GROUPS order
+    csp: Vec<Option<String>>,
+}
+
+fn parse_groups<S: AsRef<str>>(names: &[S]) -> Vec<RouteGroup> {
+    let mut groups = Vec::new();
+    for name in names.iter().map(|n| n.as_ref().trim()).filter(|n| !n.is_empty()) {
+        match GROUPS.iter().find(|group| group.name().eq_ignore_ascii_case(name)) {
+            Some(group) => groups.push(*group),
+            None => log::warn!("Ignoring unknown security header route group: {}", name),
+        }
+    }
+    groups
+}
+
+fn config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let settings = &config::get().security_headers;
+        for name in settings.csp.keys().filter(|name| !GROUPS.iter().any(|group| group.name() == name.as_str())) {
+            log::warn!("Ignoring Content-Security-Policy for unknown route group: {}", name);
+        }
+        let csp = GROUPS
+            .iter()
+            .map(|group| match settings.csp.get(group.name()) {
+                Some(policy) if policy.trim().eq_ignore_ascii_case("off") => None,
+                Some(policy) => Some(policy.trim().to_string()),
+                None => group.default_csp().map(str::to_string),
+            })
+            .collect();
+        let max_age = settings.hsts_max_age;
+        Config { groups: parse_groups(&settings.groups), hsts: (max_age > 0).then(|| format!("max-age={}", max_age)), csp }
+    })
+}
+
+/// Per-response CSP nonce, kept in the request extensions for handlers
+#[derive(Clone)]
+struct CspNonce(String);
+
+/// Nonce for this response's inline <script> tags; empty when the middleware
+/// isn't wrapped around the route
+pub fn nonce(req: &HttpRequest) -> String {
+    req.extensions().get::<CspNonce>().map(|nonce| nonce.0.clone()).unwrap_or_default()
+}
+
+/// Headers for a response in `group`
+fn headers_for(config: &Config, group: RouteGroup, nonce: &str) -> Vec<(HeaderName, String)> {
+    let mut headers = vec![
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        (header::X_FRAME_OPTIONS, if group.public() { "SAMEORIGIN" } else { "DENY" }.to_string()),
+        (header::REFERRER_POLICY, if group.public() { "strict-origin-when-cross-origin" } else { "no-referrer" }.to_string()),
+        (HeaderName::from_static("permissions-policy"), PERMISSIONS_POLICY.to_string()),
+        (HeaderName::from_static("cross-origin-opener-policy"), "same-origin".to_string()),
+        (HeaderName::from_static("cross-origin-resource-policy"), if group.public() { "same-site" } else { "same-origin" }.to_string()),
+    ];
+    if let Some(hsts) = &config.hsts {
+        headers.push((header::STRICT_TRANSPORT_SECURITY, hsts.clone()));
+    }
+    let index = GROUPS.iter().position(|g| *g == group).unwrap_or(0);
+    if let Some(policy) = &config.csp[index] {
+        headers.push((header::CONTENT_SECURITY_POLICY, policy.replace("{nonce}", nonce)));
+    }
+    headers
+}
+
+/// Middleware adding the route group's security headers to every response;
+/// headers a handler already set are kept
+pub async fn security_headers(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let config = config();
+    let group = RouteGroup::of(req.path());
+    if !config.groups.contains(&group) {
+        return next.call(req).await;
+    }
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    req.extensions_mut().insert(CspNonce(nonce.clone()));
+
+    let mut res = next.call(req).await?;
+    let response_headers = res.headers_mut();
+    for (name, value) in headers_for(config, group, &nonce) {
+        if !response_headers.contains_key(&name) {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response_headers.insert(name, value);
+            }
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{middleware::from_fn, web, App, HttpResponse};
+
+    #[test]
+    fn test_route_groups() {
+        assert_eq!(RouteGroup::of("/contact-admin"), RouteGroup::Admin);
+        assert_eq!(RouteGroup::of("/contact-admin/quotes"), RouteGroup::Admin);
+        assert_eq!(RouteGroup::of("/contact-administrator"), RouteGroup::Site);
+        assert_eq!(RouteGroup::of("/view/ab12cd34"), RouteGroup::View);
+        assert_eq!(RouteGroup::of("/api/contact"), RouteGroup::Api);
+        assert_eq!(RouteGroup::of("/wp-login.php"), RouteGroup::Decoy);
+        assert_eq!(RouteGroup::of("/projects"), RouteGroup::Site);
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        assert_eq!(parse_groups(&["admin", " View"]), vec![RouteGroup::Admin, RouteGroup::View]);
+        assert_eq!(parse_groups::<&str>(&[]), Vec::<RouteGroup>::new());
+        assert_eq!(parse_groups(&["site", "bogus"]), vec![RouteGroup::Site]);
+    }
+
+    #[test]
+    fn test_headers_for_group() {
+        let config = Config {
+            groups: GROUPS.to_vec(),
+            hsts: None,
+            csp: GROUPS.iter().map(|group| group.default_csp().map(str::to_string)).collect(),
+        };
+        let value = |headers: &[(HeaderName, String)], name: &HeaderName| headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone());
+
+        let admin = headers_for(&config, RouteGroup::Admin, "n0nce");
+        assert!(value(&admin, &header::CONTENT_SECURITY_POLICY).unwrap().contains("script-src 'nonce-n0nce'"));
+        assert_eq!(value(&admin, &header::X_FRAME_OPTIONS).as_deref(), Some("DENY"));
+        assert_eq!(value(&admin, &header::STRICT_TRANSPORT_SECURITY), None);
+
+        let site = headers_for(&config, RouteGroup::Site, "n0nce");
+        assert_eq!(value(&site, &header::REFERRER_POLICY).as_deref(), Some("strict-origin-when-cross-origin"));
+        assert!(value(&headers_for(&config, RouteGroup::Decoy, "n0nce"), &header::CONTENT_SECURITY_POLICY).is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_nonce_matches_policy() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(security_headers))
+                .route("/view/{id}", web::get().to(|req: HttpRequest| async move { HttpResponse::Ok().body(nonce(&req)) })),
+        )
+        .await;
+        let res = call_service(&app, TestRequest::get().uri("/view/ab12cd34").to_request()).await;
+        let policy = res.headers().get(header::CONTENT_SECURITY_POLICY).unwrap().to_str().unwrap().to_string();
+        assert_eq!(res.headers().get(header::X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+        let body = read_body(res).await;
+        let nonce = std::str::from_utf8(&body).unwrap();
+        assert_eq!(nonce.len(), 32);
+        assert!(policy.contains(&format!("'nonce-{}'", nonce)));
+    }
+}
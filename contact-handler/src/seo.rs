@@ -0,0 +1,194 @@
+// sitemap.xml and robots.txt
+// Both are generated once at startup from the pages the server serves. The
+// sitemap lists every public HTML page with its modification time as lastmod
+// and, for pages with a Spanish or English twin, both versions as hreflang
+// alternates. robots.txt keeps crawlers out of the admin, record and API
+// routes, the honeypot pages and every enabled decoy path, and those pages
+// are left out of the sitemap too. Both are compressed once and served with
+// the same ETags and encodings as the built assets.
+//
+// Configuration ([robots] in config.rs):
+//   disallow - Extra path prefixes to disallow (and leave out of the sitemap)
+//   index    - false to disallow everything, e.g. on a staging copy
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+use crate::asset_http::{self, Generated};
+use crate::{assets, config, decoys, html_escape, lang, public_url};
+
+/// Routes no crawler should index: the admin pages, per-customer records and
+/// form endpoints, the JS honeypot pages and the maintenance page
+const DISALLOW: &[&str] = &["/contact-admin", "/view/", "/api/", "/admin", "/wp-admin", "/phpmyadmin", "/maintenance.html", "/metrics"];
+
+const CACHE_CONTROL: &str = "public, max-age=3600";
+
+struct Page {
+    path: String,
+    twin: Option<String>,
+    /// Unix time the page last changed
+    modified: u64,
+}
+
+/// Disallowed path prefixes: the built-in ones, the decoys, then robots.disallow
+fn disallowed() -> Vec<String> {
+    let extra = config::get().robots.disallow.iter().filter(|p| p.starts_with('/')).cloned();
+
+    let mut paths: Vec<String> = Vec::new();
+    for path in DISALLOW.iter().copied().chain(decoys::decoy_paths()).map(str::to_string).chain(extra) {
+        if !paths.iter().any(|prefix| path.starts_with(prefix.as_str())) {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+/// Whether a page is one crawlers may index: not an admin, record, API,
+/// honeypot or decoy route, nor under robots.disallow
+pub fn is_public_page(path: &str) -> bool {
+    static DISALLOWED: OnceLock<Vec<String>> = OnceLock::new();
+    let url = page_url(path);
+    !DISALLOWED.get_or_init(disallowed).iter().any(|prefix| url.starts_with(prefix.as_str()))
+}
+
+/// URL path a page is listed under; the home page is "/" rather than /index.html
+pub fn page_url(path: &str) -> String {
+    if path == "index.html" {
+        "/".to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+/// Every HTML page not covered by a disallowed prefix
+fn sitemap_pages(disallow: &[String]) -> Vec<Page> {
+    let source = assets::source();
+    let fallback = asset_http::build_time().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut pages: Vec<Page> = source
+        .paths()
+        .into_iter()
+        .filter(|path| path.ends_with(".html"))
+        .filter(|path| !disallow.iter().any(|prefix| page_url(path).starts_with(prefix.as_str())))
+        .filter_map(|path| {
+            let file = source.get(&path)?;
+            let twin = lang::twin_path(&path).filter(|twin| source.get(twin).is_some());
+            Some(Page { modified: file.metadata.last_modified().unwrap_or(fallback), path, twin })
+        })
+        .collect();
+    pages.sort_by(|a, b| a.path.cmp(&b.path));
+    pages
+}
+
+fn render_sitemap(pages: &[Page]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:xhtml=\"http://www.w3.org/1999/xhtml\">\n",
+    );
+    for page in pages {
+        let lastmod = chrono::DateTime::from_timestamp(page.modified as i64, 0).map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default();
+        xml.push_str(&format!(
+            "  <url>\n    <loc>{}{}</loc>\n    <lastmod>{}</lastmod>\n",
+            html_escape(public_url()),
+            html_escape(&page_url(&page.path)),
+            lastmod
+        ));
+        if let Some(twin) = &page.twin {
+            for (hreflang, url) in lang::alternate_urls(&page.path, twin) {
+                xml.push_str(&format!("    <xhtml:link rel=\"alternate\" hreflang=\"{}\" href=\"{}\"/>\n", hreflang, html_escape(&url)));
+            }
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn render_robots(disallow: &[String], indexing: bool) -> String {
+    let mut txt = String::from("User-agent: *\n");
+    if !indexing {
+        txt.push_str("Disallow: /\n");
+        return txt;
+    }
+    for path in disallow {
+        txt.push_str(&format!("Disallow: {}\n", path));
+    }
+    txt.push_str(&format!("\nSitemap: {}/sitemap.xml\n", public_url()));
+    txt
+}
+
+fn sitemap() -> &'static Generated {
+    static SITEMAP: OnceLock<Generated> = OnceLock::new();
+    SITEMAP.get_or_init(|| Generated::new(render_sitemap(&sitemap_pages(&disallowed()))))
+}
+
+fn robots() -> &'static Generated {
+    static ROBOTS: OnceLock<Generated> = OnceLock::new();
+    ROBOTS.get_or_init(|| Generated::new(render_robots(&disallowed(), config::get().robots.index)))
+}
+
+async fn sitemap_xml(req: HttpRequest) -> HttpResponse {
+    sitemap().respond(&req, "application/xml; charset=utf-8", CACHE_CONTROL)
+}
+
+async fn robots_txt(req: HttpRequest) -> HttpResponse {
+    robots().respond(&req, "text/plain; charset=utf-8", CACHE_CONTROL)
+}
+
+/// Register /sitemap.xml and /robots.txt, generating both up front
+pub fn configure_seo(cfg: &mut web::ServiceConfig) {
+    sitemap();
+    robots();
+    cfg.route("/sitemap.xml", web::get().to(sitemap_xml)).route("/robots.txt", web::get().to(robots_txt));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixes(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sitemap_pages_skip_disallowed() {
+        let pages = sitemap_pages(&prefixes(DISALLOW));
+        let paths: Vec<&str> = pages.iter().map(|p| p.path.as_str()).collect();
+        assert!(paths.contains(&"index.html"));
+        assert!(paths.contains(&"services/data-recovery.html"));
+        for hidden in ["wp-admin.html", "admin/index.html", "phpmyadmin/index.html", "maintenance.html"] {
+            assert!(!paths.contains(&hidden), "{} should not be listed", hidden);
+        }
+        let projects = pages.iter().find(|p| p.path == "projects.html").unwrap();
+        assert_eq!(projects.twin.as_deref(), Some("projects-es.html"));
+    }
+
+    #[test]
+    fn test_render_sitemap() {
+        let pages = [
+            Page { path: "index.html".to_string(), twin: None, modified: 1_700_000_000 },
+            Page { path: "docs/index-es.html".to_string(), twin: Some("docs/index.html".to_string()), modified: 1_700_000_000 },
+        ];
+        let xml = render_sitemap(&pages);
+        assert!(xml.contains(&format!("<loc>{}/</loc>\n    <lastmod>2023-11-14</lastmod>", public_url())));
+        assert!(xml.contains(&format!("hreflang=\"en\" href=\"{}/docs/index.html\"", public_url())));
+        assert!(xml.contains(&format!("hreflang=\"x-default\" href=\"{}/docs/\"", public_url())));
+        assert_eq!(xml.matches("<url>").count(), 2);
+        assert!(xml.ends_with("</urlset>\n"));
+    }
+
+    #[test]
+    fn test_render_robots() {
+        let robots = render_robots(&prefixes(&["/contact-admin", "/.env"]), true);
+        assert!(robots.starts_with("User-agent: *\nDisallow: /contact-admin\nDisallow: /.env\n"));
+        assert!(robots.ends_with(&format!("Sitemap: {}/sitemap.xml\n", public_url())));
+        assert_eq!(render_robots(&prefixes(&["/contact-admin"]), false), "User-agent: *\nDisallow: /\n");
+    }
+
+    #[test]
+    fn test_disallowed_covers_decoys_once() {
+        let paths = disallowed();
+        assert!(paths.iter().any(|p| p == "/wp-login.php"));
+        // Covered by the built-in /phpmyadmin prefix
+        assert!(!paths.iter().any(|p| p == "/phpmyadmin/index.php"));
+    }
+}
@@ -223,11 +223,14 @@ if (!currentLang) {
     }
     localStorage.setItem('lang', currentLang);
 }
+// Mirror the choice in a cookie so the server serves matching pages
+document.cookie = 'lang=' + currentLang + '; path=/; max-age=31536000; samesite=lax';
 
 function setLanguage(lang) {
     console.log('setLanguage called with:', lang);
     currentLang = lang;
     localStorage.setItem('lang', lang);
+    document.cookie = 'lang=' + lang + '; path=/; max-age=31536000; samesite=lax';
 
     // Check if we're on a blog page and need to redirect
     var currentPath = window.location.pathname;
@@ -144,14 +144,11 @@ cmd_deploy() {
     log_info "Setting up remote directory..."
     ssh_cmd "mkdir -p $REMOTE_PATH/data"
 
-    # Stop existing service before upload
-    log_info "Stopping existing service..."
-    ssh_cmd "systemctl stop southcitycomputer 2>/dev/null || true"
-
-    # Upload optimized binary (contains all embedded assets)
+    # Upload optimized binary (contains all embedded assets) next to the
+    # running one, then swap it in; the old process keeps serving meanwhile
     log_info "Uploading optimized binary (13MB with all assets embedded)..."
-    sftp_put "$PROJECT_DIR/contact-handler/target/release/scc-server" "$REMOTE_PATH/scc-server"
-    ssh_cmd "chmod +x $REMOTE_PATH/scc-server"
+    sftp_put "$PROJECT_DIR/contact-handler/target/release/scc-server" "$REMOTE_PATH/scc-server.new"
+    ssh_cmd "chmod +x $REMOTE_PATH/scc-server.new && mv $REMOTE_PATH/scc-server.new $REMOTE_PATH/scc-server"
 
     # Upload accounts.txt if not exists on server
     ssh_cmd "test -f $REMOTE_PATH/data/accounts.txt || echo admin:changeme > $REMOTE_PATH/data/accounts.txt"
@@ -160,29 +157,44 @@ cmd_deploy() {
     log_info "Uploading maintenance page..."
     sftp_put "$PROJECT_DIR/maintenance.html" "$REMOTE_PATH/maintenance.html"
 
-    # Create systemd service for optimized binary
-    log_info "Creating systemd service..."
+    # Create systemd socket and service for optimized binary. The socket unit
+    # holds port 9000 across restarts, so requests made while the new binary
+    # starts wait instead of failing over to the maintenance page.
+    log_info "Creating systemd socket and service..."
+    local socket_content="[Unit]
+Description=South City Computer Website socket
+
+[Socket]
+ListenStream=9000
+
+[Install]
+WantedBy=sockets.target"
     local service_content="[Unit]
 Description=South City Computer Website
 After=network.target
+Requires=southcitycomputer.socket
 
 [Service]
-Type=simple
+Type=notify
 User=root
 WorkingDirectory=/root/southcitycomputer
 ExecStart=/root/southcitycomputer/scc-server
 Restart=always
 RestartSec=5
+TimeoutStopSec=45
 Environment=PORT=9000
 
 [Install]
 WantedBy=multi-user.target"
-    local encoded=$(echo "$service_content" | base64 -w0)
+    local encoded=$(echo "$socket_content" | base64 -w0)
+    ssh_cmd "echo $encoded | base64 -d > /etc/systemd/system/southcitycomputer.socket"
+    encoded=$(echo "$service_content" | base64 -w0)
     ssh_cmd "echo $encoded | base64 -d > /etc/systemd/system/southcitycomputer.service"
 
-    # Reload and start service
+    # Reload and restart; the service only needs stopping first the one time
+    # it still holds the port itself (before the socket unit existed)
     log_info "Starting optimized service..."
-    ssh_cmd "systemctl daemon-reload && systemctl enable southcitycomputer && systemctl restart southcitycomputer"
+    ssh_cmd "systemctl daemon-reload && (systemctl is-active --quiet southcitycomputer.socket || systemctl stop southcitycomputer) && systemctl enable --now southcitycomputer.socket && systemctl enable southcitycomputer && systemctl restart southcitycomputer"
 
     sleep 2
     cmd_status
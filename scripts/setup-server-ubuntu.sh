@@ -395,16 +395,30 @@ log_success "nginx configured"
 # Create systemd Service
 # =============================================================================
 
-log_info "Creating systemd service..."
+log_info "Creating systemd socket and service..."
+
+# The socket unit owns the port, so restarts don't refuse connections
+cat > "/etc/systemd/system/$SERVICE_NAME.socket" << EOF
+[Unit]
+Description=South City Computer Website socket
+
+[Socket]
+ListenStream=$APP_PORT
+
+[Install]
+WantedBy=sockets.target
+EOF
 
 cat > "/etc/systemd/system/$SERVICE_NAME.service" << EOF
 [Unit]
 Description=South City Computer Website
 After=network.target
 Wants=network-online.target
+Requires=$SERVICE_NAME.socket
 
 [Service]
-Type=simple
+Type=notify
+TimeoutStopSec=45
 User=$APP_USER
 Group=$APP_USER
 WorkingDirectory=$APP_DIR
@@ -430,7 +444,7 @@ WantedBy=multi-user.target
 EOF
 
 systemctl daemon-reload
-systemctl enable "$SERVICE_NAME"
+systemctl enable "$SERVICE_NAME.socket" "$SERVICE_NAME"
 
 log_success "systemd service created"
 
@@ -502,16 +516,14 @@ if [ ! -f "\$BINARY" ]; then
     exit 1
 fi
 
-echo "Stopping service..."
-sudo systemctl stop $SERVICE_NAME || true
-
 echo "Copying binary..."
-sudo cp "\$BINARY" "$APP_DIR/$BINARY_NAME"
-sudo chown $APP_USER:$APP_USER "$APP_DIR/$BINARY_NAME"
-sudo chmod 755 "$APP_DIR/$BINARY_NAME"
+sudo install -o $APP_USER -g $APP_USER -m 755 "\$BINARY" "$APP_DIR/$BINARY_NAME.new"
+sudo mv "$APP_DIR/$BINARY_NAME.new" "$APP_DIR/$BINARY_NAME"
 
-echo "Starting service..."
-sudo systemctl start $SERVICE_NAME
+# The socket unit keeps the port open while the old process drains and the
+# new one starts
+echo "Restarting service..."
+sudo systemctl restart $SERVICE_NAME
 
 echo "Checking status..."
 sleep 2